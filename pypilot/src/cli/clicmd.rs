@@ -1,5 +1,12 @@
 use super::styles;
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use shared::{
+    diff::DiffFormat,
+    info::{Column, Filter, SortKey},
+    licenses::LicenseFormat,
+    tree::TreeFormat,
+};
+use std::path::PathBuf;
 use styles::custom_styles;
 
 #[derive(Debug, Parser)]
@@ -25,7 +32,8 @@ pub enum Commands {
     #[command(
         name = "install-uv",
         about = "Install Astral UV",
-        long_about = "This command installs Astral UV"
+        long_about = "This command installs Astral UV",
+        disable_version_flag = true
     )]
     Install {
         #[arg(
@@ -35,6 +43,30 @@ pub enum Commands {
             default_value = "false"
         )]
         update: bool,
+        #[arg(
+            short = 'v',
+            long,
+            help = "Install a specific version of Astral UV, e.g. 0.4.18"
+        )]
+        version: Option<String>,
+        #[arg(
+            long,
+            conflicts_with_all = ["update", "version"],
+            help = "Install from a downloaded uv archive (.tar.gz or .zip) or directory"
+        )]
+        from: Option<PathBuf>,
+        #[arg(
+            long,
+            requires = "from",
+            help = "Expected SHA-256 checksum, read from <archive>.sha256 if omitted"
+        )]
+        sha256: Option<String>,
+        #[arg(
+            long,
+            requires = "from",
+            help = "Directory to install uv into, defaults to the uv_bin_dir setting"
+        )]
+        bin_dir: Option<PathBuf>,
     },
 
     #[command(
@@ -46,9 +78,15 @@ pub enum Commands {
     #[command(
         name = "uninstall-uv",
         about = "Uninstall Astral UV",
-        long_about = "This command uninstalls Astral UV"
+        long_about = "This command detects how Astral UV was installed and uninstalls it the same way"
     )]
-    Uninstall,
+    Uninstall {
+        #[arg(
+            long,
+            help = "Also remove the uv cache and uv managed Python installations"
+        )]
+        clean: bool,
+    },
 
     #[command(
         about = "Create a new python virtual environment",
@@ -84,22 +122,278 @@ pub enum Commands {
         name_pos: Option<String>,
         #[arg(short, long, help = "Use default packages")]
         default: bool,
+        #[command(flatten)]
+        index: IndexArgs,
     },
     #[command(
-        about = "Delete a python virtual environment",
-        long_about = "This command deletes a python virtual environment"
+        about = "Add packages to a python virtual environment",
+        long_about = "This command installs packages into an existing python virtual environment"
+    )]
+    Add {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: String,
+        #[arg(index = 2, required = true, help = "Packages to install")]
+        packages: Vec<String>,
+        #[command(flatten)]
+        index: IndexArgs,
+    },
+    #[command(
+        about = "Delete python virtual environments",
+        long_about = "This command deletes one or more python virtual environments, selected by name or glob pattern"
     )]
     Delete {
-        #[arg(short, long, help = "Name of the virtual environment")]
+        #[arg(short, long, help = "Name or glob pattern of the virtual environment")]
+        name: Vec<String>,
+        #[arg(index = 1, help = "Names or glob patterns of the virtual environments")]
+        name_pos: Vec<String>,
+    },
+    #[command(
+        about = "Rename a python virtual environment",
+        long_about = "This command renames a python virtual environment and updates the paths stored inside it"
+    )]
+    Rename {
+        #[arg(index = 1, help = "Current name of the virtual environment")]
+        old: String,
+        #[arg(index = 2, help = "New name of the virtual environment")]
+        new: String,
+    },
+    #[command(
+        about = "Check python virtual environments for problems",
+        long_about = "This command checks python virtual environments for missing base interpreters, broken symlinks and inconsistent package metadata"
+    )]
+    Doctor {
+        #[arg(
+            index = 1,
+            help = "Name of the virtual environment, all environments if omitted"
+        )]
         name: Option<String>,
-        #[arg(index = 1, help = "Name of the virtual environment")]
-        name_pos: Option<String>,
+        #[arg(
+            long,
+            help = "Recreate broken environments from their recorded packages"
+        )]
+        fix: bool,
     },
+    #[command(
+        about = "Delete python virtual environments that have not been used for a while",
+        long_about = "This command deletes python virtual environments that have not been activated within the given age"
+    )]
+    Prune {
+        #[arg(
+            short,
+            long,
+            help = "Age of environments to delete, e.g. 12h, 90d or 8w",
+            default_value = "90d"
+        )]
+        older_than: String,
+        #[arg(long, help = "Only list the environments that would be deleted")]
+        dry_run: bool,
+    },
+    #[command(
+        name = "du",
+        about = "Show disk usage of python virtual environments",
+        long_about = "This command shows the disk usage of each python virtual environment, split into bytes unique to the environment and bytes shared with the uv cache or other environments"
+    )]
+    Du,
     #[command(
         about = "List all python virtual environments",
         long_about = "This command lists all python virtual environments"
     )]
-    List,
+    List {
+        #[arg(
+            short,
+            long,
+            value_delimiter = ',',
+            help = "Extra columns to show: size, packages, created, used, path, status, tags, description"
+        )]
+        columns: Vec<Column>,
+        #[arg(
+            short,
+            long,
+            help = "Sort by name, size, created or used",
+            default_value = "name"
+        )]
+        sort: SortKey,
+        #[arg(
+            short,
+            long,
+            help = "Only show matching environments, e.g. python=3.11"
+        )]
+        filter: Vec<Filter>,
+        #[arg(short, long, help = "Only show environments with this tag")]
+        tag: Vec<String>,
+    },
+    #[command(
+        about = "Add or remove tags of a python virtual environment",
+        long_about = "This command adds (+tag) or removes (-tag) tags of a python virtual environment"
+    )]
+    Tag {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: String,
+        #[arg(
+            index = 2,
+            required = true,
+            allow_hyphen_values = true,
+            help = "Tags to add (+tag) or remove (-tag)"
+        )]
+        tags: Vec<String>,
+    },
+    #[command(
+        about = "Set the description of a python virtual environment",
+        long_about = "This command sets the description of a python virtual environment, an empty text removes it"
+    )]
+    Describe {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: String,
+        #[arg(index = 2, help = "Description of the virtual environment")]
+        description: String,
+    },
+    #[command(
+        about = "Show details of a python virtual environment",
+        long_about = "This command shows the details and snapshots of a python virtual environment"
+    )]
+    Info {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: String,
+    },
+    #[command(
+        about = "Compare the packages of python virtual environments",
+        long_about = "This command shows packages only in one environment and version differences between two environments, or between an environment and a requirements file"
+    )]
+    Diff {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: String,
+        #[arg(
+            index = 2,
+            required_unless_present = "requirements",
+            conflicts_with = "requirements",
+            help = "Name of the virtual environment to compare with"
+        )]
+        other: Option<String>,
+        #[arg(short, long, help = "Requirements file to compare with")]
+        requirements: Option<PathBuf>,
+        #[arg(
+            short,
+            long,
+            help = "Output format: table or json",
+            default_value = "table"
+        )]
+        format: DiffFormat,
+    },
+    #[command(
+        about = "List packages with newer versions available",
+        long_about = "This command lists the installed packages of python virtual environments that have newer versions available from the configured index or find-links directories"
+    )]
+    Outdated {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: Option<String>,
+        #[arg(long, conflicts_with = "name", help = "Check all virtual environments")]
+        all: bool,
+        #[arg(long, help = "Upgrade the outdated packages to the latest version")]
+        upgrade: bool,
+    },
+    #[command(
+        about = "Check installed packages against known vulnerabilities",
+        long_about = "This command checks the installed packages of python virtual environments against a local OSV advisory database and exits with an error when vulnerable packages are found"
+    )]
+    Audit {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: Option<String>,
+        #[arg(long, conflicts_with = "name", help = "Audit all virtual environments")]
+        all: bool,
+        #[arg(long, help = "Download the advisory database before auditing")]
+        sync: bool,
+    },
+    #[command(
+        about = "List the licenses of installed packages",
+        long_about = "This command lists the licenses of the installed packages of a python virtual environment and exits with an error when a license violates the allow or deny lists in the settings"
+    )]
+    Licenses {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: String,
+        #[arg(
+            short,
+            long,
+            help = "Output format: table, csv or json",
+            default_value = "table"
+        )]
+        format: LicenseFormat,
+    },
+    #[command(
+        about = "Show the dependency tree of a python virtual environment",
+        long_about = "This command shows the installed packages of a python virtual environment as a dependency tree and highlights missing or conflicting dependencies"
+    )]
+    Tree {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: String,
+        #[arg(index = 2, help = "Package to show the dependencies of")]
+        package: Option<String>,
+        #[arg(
+            long,
+            value_name = "PACKAGE",
+            conflicts_with = "package",
+            help = "Show the packages that require a package"
+        )]
+        reverse: Option<String>,
+        #[arg(
+            short,
+            long,
+            help = "Output format: text, json or dot",
+            default_value = "text"
+        )]
+        format: TreeFormat,
+    },
+    #[command(
+        about = "Record the installed packages of a python virtual environment",
+        long_about = "This command records the exact installed package set of a python virtual environment so it can be restored with rollback"
+    )]
+    Snapshot {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: String,
+        #[arg(index = 2, help = "Label of the snapshot")]
+        label: Option<String>,
+        #[arg(long, help = "Also store a compressed copy of the environment")]
+        archive: bool,
+    },
+    #[command(
+        about = "Restore a snapshot of a python virtual environment",
+        long_about = "This command restores the packages of a python virtual environment from a snapshot, the latest snapshot if none is given"
+    )]
+    Rollback {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: String,
+        #[arg(index = 2, help = "Id or label of the snapshot")]
+        snapshot: Option<String>,
+    },
+    #[command(
+        about = "Pack a python virtual environment into an archive",
+        long_about = "This command bundles the manifest, the pinned package list and optionally the wheels of a python virtual environment into a .tar.zst archive"
+    )]
+    Pack {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: String,
+        #[arg(short, long, help = "Archive to write, defaults to <name>.tar.zst")]
+        output: Option<PathBuf>,
+        #[arg(long, help = "Include the wheels of the installed packages")]
+        wheels: bool,
+    },
+    #[command(
+        about = "Recreate a python virtual environment from an archive",
+        long_about = "This command recreates a python virtual environment from an archive created with pack, using the included wheels when present"
+    )]
+    Unpack {
+        #[arg(index = 1, help = "Archive created with pack")]
+        archive: PathBuf,
+        #[arg(short, long, help = "Name of the new virtual environment")]
+        name: Option<String>,
+    },
+    #[command(
+        about = "Manage local directories of wheels",
+        long_about = "This command manages local directories of wheels used to create environments offline"
+    )]
+    Wheelhouse {
+        #[command(subcommand)]
+        command: WheelhouseCommands,
+    },
     #[command(
         about = "Activate a python virtual environment",
         long_about = "This command activates a python virtual environment in its own shell"
@@ -112,6 +406,35 @@ pub enum Commands {
     },
 }
 
+#[derive(Debug, Default, Args)]
+pub struct IndexArgs {
+    #[arg(long, help = "Package index URL to install from")]
+    pub index_url: Option<String>,
+    #[arg(long, help = "Index profile from the settings file to install from")]
+    pub profile: Option<String>,
+    #[arg(long, help = "Do not access the network, only use cached packages")]
+    pub offline: bool,
+    #[arg(
+        long,
+        help = "Directory of wheels to install from instead of a package index, implies --offline"
+    )]
+    pub wheelhouse: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WheelhouseCommands {
+    #[command(
+        about = "Collect the wheels of a python virtual environment",
+        long_about = "This command packs the installed packages of a python virtual environment into wheels and writes a requirements.txt next to them"
+    )]
+    Build {
+        #[arg(index = 1, help = "Name of the virtual environment")]
+        name: String,
+        #[arg(index = 2, help = "Directory to write the wheels to")]
+        dir: PathBuf,
+    },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
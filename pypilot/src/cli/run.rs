@@ -1,7 +1,18 @@
+use super::clicmd::IndexArgs;
 use colored::Colorize;
 use shared::venvmanager;
-use shared::{constants::ERROR_CREATING_VENV, processes, utils, uv, venv};
-use std::io;
+use shared::{
+    audit::{self, AdvisoryDb},
+    constants::{ERROR_CREATING_VENV, ERROR_VENV_NOT_EXISTS},
+    diff, doctor, info,
+    licenses::{self, LicenseFormat},
+    outdated, pack, processes, settings, snapshot,
+    tree::{self, TreeFormat},
+    usage, utils, uv, validation, venv,
+    version::Version,
+    wheelhouse,
+};
+use std::{io, path::PathBuf};
 
 pub async fn activate(name_pos: Option<String>, name: Option<String>) {
     let venv = venvmanager::VENVMANAGER
@@ -17,11 +28,33 @@ pub async fn check() {
         "{}",
         "Checking if Astral UV is installed and configured...".cyan()
     );
-    if uv::check().await {
-        println!("{}", "Astral UV is installed".green());
+    if !uv::check().await {
+        println!("{}", "Astral UV was not found".red());
         return;
     }
-    println!("{}", "Astral UV was not found".red());
+    let min = uv::min_version();
+    match uv::version().await {
+        Some(installed) if installed >= min => println!(
+            "{}",
+            format!(
+                "Astral UV {} is installed (minimum required {})",
+                installed, min
+            )
+            .green()
+        ),
+        Some(installed) => println!(
+            "{}",
+            format!(
+                "Astral UV {} is installed but {} or newer is required, run 'pypilot install-uv --update'",
+                installed, min
+            )
+            .red()
+        ),
+        None => println!(
+            "{}",
+            "Astral UV is installed but its version could not be determined".yellow()
+        ),
+    }
 }
 
 pub async fn create(
@@ -31,6 +64,7 @@ pub async fn create(
     packages: Vec<String>,
     requirements: String,
     default: bool,
+    index: IndexArgs,
 ) {
     let name = match name.or(name_pos) {
         Some(n) => n,
@@ -38,10 +72,11 @@ pub async fn create(
             processes::exit_with_error("Missing name for the environment.");
         }
     };
-    if !uv::check().await {
-        processes::exit_with_error(
-            "Astral UV is not installed. Please run 'uv install' to install it.",
-        );
+    if let Err(e) = validation::validate_name(&name) {
+        processes::exit_with_error(&e);
+    }
+    if let Err(e) = uv::ensure_supported().await {
+        processes::exit_with_error(&e);
     }
     if venvmanager::VENVMANAGER.check_if_exists(name.clone()).await {
         processes::exit_with_error("Virtual environment with this name already exists.");
@@ -55,50 +90,728 @@ pub async fn create(
             }
         }
     }
-    let venv = venv::Venv::new(name, "".to_string(), python_version, packages, default);
+    let mut venv = venv::Venv::new(name, python_version, packages, default);
+    if let Err(e) = apply_index(&mut venv, index) {
+        processes::exit_with_error(&e);
+    }
+    if let Err(e) = venv.index() {
+        processes::exit_with_error(&e);
+    }
     if let Err(e) = venv.create().await {
         eprintln!("{}", format!("{}: {}", ERROR_CREATING_VENV, e).red());
         venv.delete(false).await;
     }
 }
 
-pub async fn delete(name_pos: Option<String>, name: Option<String>) {
-    let venv = venvmanager::VENVMANAGER
-        .find_venv(name_pos, name, "delete")
-        .await;
-    if let Some(v) = venv {
-        v.delete(true).await
+pub async fn add(name: String, packages: Vec<String>, index: IndexArgs) {
+    let Some(mut venv) = find_existing(name, "add packages to").await else {
+        return;
+    };
+    let explicit = index.profile.is_some() || index.index_url.is_some();
+    if let Err(e) = apply_index(&mut venv, index) {
+        processes::exit_with_error(&e);
+    }
+    if let Err(e) = venv.install(&packages).await {
+        processes::exit_with_error(&format!("Error adding packages to {}: {}", venv.name, e));
+    }
+    if explicit {
+        if let Err(e) = venv.save_index() {
+            eprintln!("{}", e.red());
+        }
+    }
+    println!(
+        "{} {}",
+        venv.name.cyan(),
+        "packages have been added".green()
+    );
+}
+
+fn apply_index(venv: &mut venv::Venv, index: IndexArgs) -> Result<(), String> {
+    if index.profile.is_some() || index.index_url.is_some() {
+        venv.index_profile = index.profile;
+        venv.index_url = index.index_url;
+        venv.wheelhouse = None;
+        venv.offline = false;
+    }
+    if let Some(wheelhouse) = index.wheelhouse {
+        let wheelhouse = wheelhouse
+            .canonicalize()
+            .map_err(|e| format!("Wheelhouse {}: {}", wheelhouse.display(), e))?;
+        venv.wheelhouse = Some(wheelhouse);
+    }
+    venv.offline |= index.offline;
+    Ok(())
+}
+
+pub async fn info(name: String) {
+    let Some(venv) = find_existing(name, "show").await else {
+        return;
+    };
+    let snapshots = snapshot::list(&venv);
+    let columns = vec![
+        info::Column::Size,
+        info::Column::Packages,
+        info::Column::Created,
+        info::Column::Used,
+    ];
+    let info = info::VenvInfo::collect(venv, columns).await;
+    venvmanager::VENVMANAGER.print_details(&info, &snapshots);
+}
+
+pub async fn diff(
+    name: String,
+    other: Option<String>,
+    requirements: Option<PathBuf>,
+    format: diff::DiffFormat,
+) {
+    let Some(venv) = find_existing(name, "compare").await else {
+        return;
+    };
+    let result = match (other, requirements) {
+        (_, Some(requirements)) => {
+            let lines = utils::read_requirements_file(&requirements.to_string_lossy()).await;
+            diff::parse_requirements(&lines).map(|reqs| {
+                diff::compare_requirements(&venv, &requirements.to_string_lossy(), &reqs)
+            })
+        }
+        (Some(other), None) => {
+            let Some(other) = find_existing(other, "compare").await else {
+                return;
+            };
+            Ok(diff::compare_venvs(&venv, &other))
+        }
+        (None, None) => Err("Missing environment or requirements file to compare with".to_string()),
+    };
+    let report = result.unwrap_or_else(|e| processes::exit_with_error(&e));
+    match format {
+        diff::DiffFormat::Table => venvmanager::VENVMANAGER.print_diff_table(&report),
+        diff::DiffFormat::Json => match report.to_json() {
+            Ok(json) => println!("{}", json),
+            Err(e) => processes::exit_with_error(&e),
+        },
     }
 }
 
-pub async fn install(update: bool) {
-    if uv::check().await && !update {
-        println!("{}", "Astral UV is already installed.".yellow());
+pub async fn outdated(name: Option<String>, all: bool, upgrade: bool) {
+    let venvs = if all {
+        venvmanager::VENVMANAGER.list().await
+    } else {
+        venvmanager::VENVMANAGER
+            .find_venv(name, None, "check")
+            .await
+            .into_iter()
+            .filter(|v| v.path.exists())
+            .collect()
+    };
+    if venvs.is_empty() {
+        println!("{}", "No virtual environments found".yellow());
         return;
     }
-    if let Err(e) = uv::install(io::stdin()).await {
-        eprintln!("{}", format!("Error installing Astral UV: {}", e).red());
+    let mut report = Vec::new();
+    let mut failed = Vec::new();
+    for venv in venvs {
+        match outdated::check(&venv).await {
+            Ok(packages) if packages.is_empty() => {}
+            Ok(packages) => report.push((venv, packages)),
+            Err(e) => {
+                eprintln!("{}", format!("Error checking {}: {}", venv.name, e).red());
+                failed.push(venv.name);
+            }
+        }
+    }
+    let check_failed = || {
+        if !failed.is_empty() {
+            processes::exit_with_error(&format!("Could not check {}", failed.join(", ")));
+        }
+    };
+    if report.is_empty() {
+        check_failed();
+        println!("{}", "All packages are up to date".green());
+        return;
     }
+    let table: Vec<_> = report
+        .iter()
+        .map(|(venv, packages)| (venv.name.clone(), packages.clone()))
+        .collect();
+    venvmanager::VENVMANAGER.print_outdated_table(&table);
+    if upgrade {
+        for (venv, packages) in report {
+            let pkgs: Vec<String> = packages.iter().map(|p| p.requirement()).collect();
+            match venv.install(&pkgs).await {
+                Ok(_) => println!("{} {}", venv.name.cyan(), "has been upgraded".green()),
+                Err(e) => eprintln!("{}", format!("Error upgrading {}: {}", venv.name, e).red()),
+            }
+        }
+    }
+    check_failed();
 }
 
-pub async fn uninstall() {
-    if !uv::check().await {
-        processes::exit_with_error("Astral UV is not installed.");
+pub async fn audit(name: Option<String>, all: bool, sync: bool) {
+    let settings = settings::Settings::get_settings();
+    let db_dir = settings.advisory_db();
+    if sync {
+        let url = settings.advisory_url();
+        println!("{} {}", "Downloading advisories from".cyan(), url);
+        match audit::sync(&db_dir, &url).await {
+            Ok(db) => println!(
+                "{}",
+                format!("{} advisories stored in {}", db.len(), db_dir.display()).green()
+            ),
+            Err(e) => processes::exit_with_error(&e),
+        }
+        if name.is_none() && !all {
+            return;
+        }
     }
-    if let Err(e) = uv::uninstall(io::stdin()).await {
-        eprintln!("{}", format!("Error uninstalling Astral UV: {}", e).red());
+    let db = AdvisoryDb::load(&db_dir).unwrap_or_else(|e| processes::exit_with_error(&e));
+    if db.skipped_records > 0 || db.skipped_events > 0 {
+        println!(
+            "{}",
+            format!(
+                "Skipped {} unreadable advisories and {} unreadable version events",
+                db.skipped_records, db.skipped_events
+            )
+            .yellow()
+        );
+    }
+    let venvs = if all {
+        venvmanager::VENVMANAGER.list().await
+    } else {
+        venvmanager::VENVMANAGER
+            .find_venv(name, None, "audit")
+            .await
+            .into_iter()
+            .filter(|v| v.path.exists())
+            .collect()
+    };
+    if venvs.is_empty() {
+        println!("{}", "No virtual environments found".yellow());
+        return;
+    }
+    let report: Vec<_> = venvs
+        .iter()
+        .map(|venv| (venv.name.clone(), audit::check(venv, &db)))
+        .filter(|(_, findings)| !findings.is_empty())
+        .collect();
+    if report.is_empty() {
+        println!("{}", "No known vulnerabilities found".green());
+        return;
+    }
+    venvmanager::VENVMANAGER.print_audit_table(&report);
+    let count: usize = report.iter().map(|(_, findings)| findings.len()).sum();
+    processes::exit_with_error(&format!("{} known vulnerabilities found", count));
+}
+
+pub async fn licenses(name: String, format: LicenseFormat) {
+    let Some(venv) = find_existing(name, "inspect").await else {
+        return;
+    };
+    let policy = settings::Settings::get_settings().licenses;
+    let packages = licenses::inventory(&venv, &policy);
+    match format {
+        LicenseFormat::Table => {
+            venvmanager::VENVMANAGER.print_license_table(&packages, !policy.is_empty())
+        }
+        LicenseFormat::Csv => print!("{}", licenses::to_csv(&packages)),
+        LicenseFormat::Json => match licenses::to_json(&packages) {
+            Ok(json) => println!("{}", json),
+            Err(e) => processes::exit_with_error(&e),
+        },
+    }
+    let violations = licenses::violations(&packages);
+    if violations > 0 {
+        processes::exit_with_error(&format!(
+            "{} packages violate the license policy",
+            violations
+        ));
+    }
+}
+
+pub async fn tree(
+    name: String,
+    package: Option<String>,
+    reverse: Option<String>,
+    format: TreeFormat,
+) {
+    let Some(venv) = find_existing(name, "inspect").await else {
+        return;
+    };
+    let graph = tree::Graph::new(&venv.distributions(), &venv.marker_env());
+    let start = match package.as_ref().or(reverse.as_ref()) {
+        Some(package) => match graph.find(package) {
+            Some(key) => vec![key],
+            None => processes::exit_with_error(&format!(
+                "Package {} is not installed in {}",
+                package, venv.name
+            )),
+        },
+        None => graph.roots(),
+    };
+    let reverse = reverse.is_some();
+    match format {
+        TreeFormat::Text => print!("{}", tree::to_text(&graph, &start, reverse)),
+        TreeFormat::Json => match tree::to_json(&graph, &start, reverse) {
+            Ok(json) => println!("{}", json),
+            Err(e) => processes::exit_with_error(&e),
+        },
+        TreeFormat::Dot => print!("{}", tree::to_dot(&graph, &start, reverse)),
+    }
+    if format != TreeFormat::Text {
+        return;
+    }
+    for (package, dependency) in graph.conflicts(&start, reverse) {
+        let installed = graph
+            .packages
+            .get(&dependency.key)
+            .map_or("not installed".to_string(), |p| {
+                format!("{} is installed", p.version)
+            });
+        let required = match dependency.required.as_str() {
+            "any" => dependency.name.clone(),
+            spec => format!("{}{}", dependency.name, spec),
+        };
+        eprintln!(
+            "{}",
+            format!("{} requires {}, {}", package.name, required, installed).red()
+        );
+    }
+}
+
+pub async fn snapshot(name: String, label: Option<String>, archive: bool) {
+    let Some(venv) = find_existing(name, "snapshot").await else {
+        return;
+    };
+    match snapshot::create(&venv, label, archive) {
+        Ok(s) => println!(
+            "{} {} {}",
+            venv.name.cyan(),
+            "snapshot created:".green(),
+            s.id.green()
+        ),
+        Err(e) => processes::exit_with_error(&e),
     }
 }
 
-pub async fn list() {
-    let mut venvs = venvmanager::VENVMANAGER.list().await;
+pub async fn rollback(name: String, id: Option<String>) {
+    let Some(venv) = find_existing(name, "roll back").await else {
+        return;
+    };
+    let snapshot = match snapshot::find(&venv, id.as_deref()) {
+        Ok(s) => s,
+        Err(e) => processes::exit_with_error(&e),
+    };
+    println!(
+        "{} {} {}",
+        "Rolling back".cyan(),
+        venv.name.cyan(),
+        format!("to snapshot {}", snapshot.id).cyan()
+    );
+    if let Err(e) = snapshot::rollback(&venv, &snapshot).await {
+        processes::exit_with_error(&format!("Error rolling back {}: {}", venv.name, e));
+    }
+    println!("{} {}", venv.name.cyan(), "has been rolled back".green());
+}
+
+pub async fn pack(name: String, output: Option<PathBuf>, wheels: bool) {
+    let Some(venv) = find_existing(name, "pack").await else {
+        return;
+    };
+    let output = output.unwrap_or_else(|| PathBuf::from(format!("{}.tar.zst", venv.name)));
+    let packed = match pack::pack(&venv, &output, wheels) {
+        Ok(packed) => packed,
+        Err(e) => processes::exit_with_error(&e),
+    };
+    for (package, reason) in &packed.skipped {
+        println!(
+            "{} {} ({}), {}",
+            "No wheel for".yellow(),
+            package.cyan(),
+            reason.yellow(),
+            "left out of the pack".yellow()
+        );
+    }
+    for (package, files) in &packed.outside {
+        println!(
+            "{} {} {}",
+            "Skipped files outside site-packages in".yellow(),
+            package.cyan(),
+            format!("({})", files.join(", ")).yellow()
+        );
+    }
+    println!(
+        "{}",
+        format!(
+            "Packed {} with {} package(s) into {}",
+            venv.name,
+            packed.manifest.packages.len(),
+            output.display()
+        )
+        .green()
+    );
+}
+
+pub async fn unpack(archive: PathBuf, name: Option<String>) {
+    let dir = std::env::temp_dir().join(format!("pypilot-unpack-{}", std::process::id()));
+    let manifest = match pack::unpack(&archive, &dir) {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&dir);
+            processes::exit_with_error(&e);
+        }
+    };
+    let mut venv = manifest.venv(name, &dir);
+    let result = match validation::validate_name(&venv.name) {
+        Err(e) => Err(e),
+        Ok(_)
+            if venvmanager::VENVMANAGER
+                .check_if_exists(venv.name.clone())
+                .await =>
+        {
+            Err("Virtual environment with this name already exists.".to_string())
+        }
+        Ok(_) => uv::ensure_supported().await,
+    };
+    if let Err(e) = result {
+        let _ = std::fs::remove_dir_all(&dir);
+        processes::exit_with_error(&e);
+    }
+    let result = pack::create(&mut venv).await;
+    let _ = std::fs::remove_dir_all(&dir);
+    match result {
+        Ok(_) => println!("{} {}", venv.name.cyan(), "has been unpacked".green()),
+        Err(e) => {
+            eprintln!("{}", format!("{}: {}", ERROR_CREATING_VENV, e).red());
+            venv.delete(false).await;
+        }
+    }
+}
+
+pub async fn wheelhouse_build(name: String, dir: PathBuf) {
+    let Some(venv) = find_existing(name, "build a wheelhouse from").await else {
+        return;
+    };
+    let report = match wheelhouse::build(&venv, &dir) {
+        Ok(report) => report,
+        Err(e) => processes::exit_with_error(&e),
+    };
+    for (package, reason) in &report.skipped {
+        println!(
+            "{} {} ({})",
+            "Skipped".yellow(),
+            package.cyan(),
+            reason.yellow()
+        );
+    }
+    for (package, files) in &report.outside {
+        println!(
+            "{} {} {}",
+            "Skipped files outside site-packages in".yellow(),
+            package.cyan(),
+            format!("({})", files.join(", ")).yellow()
+        );
+    }
+    println!(
+        "{}",
+        format!(
+            "Collected {} wheel(s) from {} into {}",
+            report.built.len(),
+            venv.name,
+            dir.display()
+        )
+        .green()
+    );
+}
+
+pub async fn delete(names: Vec<String>) {
+    let mut venvs = venvmanager::VENVMANAGER.find_venvs(names, "delete").await;
+    venvs.retain(|v| {
+        let exists = v.path.exists();
+        if !exists {
+            eprintln!("{}: {}", ERROR_VENV_NOT_EXISTS.red(), v.name);
+        }
+        exists
+    });
+    if venvs.is_empty() {
+        return;
+    }
+    println!("{}", "Deleting virtual environment(s):".yellow());
+    for v in &venvs {
+        println!(
+            "  {} {} {}",
+            v.name.red(),
+            "at".yellow(),
+            v.path.display().to_string().red()
+        );
+    }
+    if !utils::confirm(io::stdin()) {
+        return;
+    }
+    delete_all(venvs);
+}
+
+fn delete_all(venvs: Vec<venv::Venv>) {
+    let results: Vec<(String, Result<(), String>)> = venvs
+        .into_iter()
+        .map(|v| (v.name.clone(), v.remove()))
+        .collect();
+    for (name, result) in &results {
+        match result {
+            Ok(_) => println!("{} {}", name.red(), "has been deleted".green()),
+            Err(e) => eprintln!(
+                "{} {}",
+                name.yellow(),
+                format!("was not deleted: {}", e).red()
+            ),
+        }
+    }
+    let failed = results.iter().filter(|(_, r)| r.is_err()).count();
+    if results.len() > 1 {
+        println!(
+            "{}",
+            format!(
+                "Deleted {} of {} environment(s)",
+                results.len() - failed,
+                results.len()
+            )
+            .cyan()
+        );
+    }
+}
+
+pub async fn rename(old: String, new: String) {
+    let Some(mut v) = find_existing(old, "rename").await else {
+        return;
+    };
+    let old_name = v.name.clone();
+    match v.rename(&new).await {
+        Ok(_) => println!(
+            "{} {} {}",
+            old_name.cyan(),
+            "has been renamed to".green(),
+            v.name.cyan()
+        ),
+        Err(e) => eprintln!("{}", format!("Error renaming {}: {}", old_name, e).red()),
+    }
+}
+
+pub async fn doctor(name: Option<String>, fix: bool) {
+    let venvs = match name {
+        Some(n) => {
+            let Some(venv) = find_existing(n, "check").await else {
+                return;
+            };
+            vec![venv]
+        }
+        None => venvmanager::VENVMANAGER.list().await,
+    };
+    let uv_installed = uv::check().await;
+    if !uv_installed {
+        doctor::print_issues("uv", &[doctor::Issue::UvMissing]);
+    }
+    if venvs.is_empty() {
+        println!("{}", "No virtual environments found".yellow());
+        return;
+    }
+    let mut broken = Vec::new();
+    for venv in venvs {
+        let issues = doctor::check(&venv).await;
+        doctor::print_issues(&venv.name, &issues);
+        if issues
+            .iter()
+            .any(|i| i.fixable() && i.severity() == doctor::Severity::Error)
+        {
+            broken.push(venv);
+        }
+    }
+    if !fix || broken.is_empty() {
+        return;
+    }
+    if !uv_installed {
+        processes::exit_with_error("Astral UV is required to repair environments.");
+    }
+    println!(
+        "{} {}",
+        "Recreating environment(s):".yellow(),
+        broken
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+            .red()
+    );
+    if !utils::confirm(io::stdin()) {
+        return;
+    }
+    for venv in broken {
+        match doctor::fix(&venv).await {
+            Ok(_) => println!("{} {}", venv.name.cyan(), "has been repaired".green()),
+            Err(e) => eprintln!("{}", format!("Error repairing {}: {}", venv.name, e).red()),
+        }
+    }
+}
+
+pub async fn prune(older_than: String, dry_run: bool) {
+    let age = utils::parse_age(&older_than).unwrap_or_else(|e| processes::exit_with_error(&e));
+    let cutoff = utils::cutoff(age).unwrap_or_else(|e| processes::exit_with_error(&e));
+    let venvs = venvmanager::VENVMANAGER.list().await;
+    let columns = [
+        info::Column::Size,
+        info::Column::Created,
+        info::Column::Used,
+    ];
+    let mut stale = venvmanager::VENVMANAGER.collect_info(venvs, &columns).await;
+    stale.retain(|i| i.is_stale(cutoff));
+    if stale.is_empty() {
+        println!(
+            "{}",
+            format!("No virtual environments unused for {}", older_than).green()
+        );
+        return;
+    }
+    info::sort_infos(&mut stale, info::SortKey::Size);
+    venvmanager::VENVMANAGER.print_venv_table(&stale, &columns);
+    let total: u64 = stale.iter().filter_map(|i| i.size).sum();
+    println!(
+        "{} {} {}",
+        format!("{} environment(s) unused for {},", stale.len(), older_than).yellow(),
+        utils::format_size(total).red(),
+        "in total".yellow()
+    );
+    if dry_run || !utils::confirm(io::stdin()) {
+        return;
+    }
+    delete_all(stale.into_iter().map(|i| i.venv).collect());
+}
+
+pub async fn du() {
+    let venvs = venvmanager::VENVMANAGER.list().await;
     if venvs.is_empty() {
         println!("{}", "No virtual environments found".yellow());
+        return;
+    }
+    let mut report = tokio::task::spawn_blocking(move || usage::disk_usage(&venvs))
+        .await
+        .unwrap_or_else(|e| processes::exit_with_error(&e.to_string()));
+    report.envs.sort_by_key(|e| std::cmp::Reverse(e.unique));
+    venvmanager::VENVMANAGER.print_usage_table(&report);
+}
+
+pub async fn tag(name: String, tags: Vec<String>) {
+    let Some(mut venv) = find_existing(name, "tag").await else {
+        return;
+    };
+    let (remove, add): (Vec<String>, Vec<String>) =
+        tags.into_iter().partition(|t| t.starts_with('-'));
+    let add: Vec<String> = add
+        .iter()
+        .map(|t| t.trim_start_matches('+').to_string())
+        .collect();
+    let remove: Vec<String> = remove
+        .iter()
+        .map(|t| t.trim_start_matches('-').to_string())
+        .collect();
+    match venv.update_tags(&add, &remove) {
+        Ok(_) if venv.tags.is_empty() => {
+            println!("{} {}", venv.name.cyan(), "has no tags".yellow())
+        }
+        Ok(_) => println!(
+            "{} {} {}",
+            venv.name.cyan(),
+            "is tagged".green(),
+            venv.tags.join(", ").green()
+        ),
+        Err(e) => eprintln!("{}", e.red()),
+    }
+}
+
+pub async fn describe(name: String, description: String) {
+    let Some(mut venv) = find_existing(name, "describe").await else {
+        return;
+    };
+    match venv.set_description(Some(description)) {
+        Ok(_) => println!(
+            "{} {}",
+            venv.name.cyan(),
+            "description has been updated".green()
+        ),
+        Err(e) => eprintln!("{}", e.red()),
+    }
+}
+
+async fn find_existing(name: String, method: &str) -> Option<venv::Venv> {
+    let venv = venvmanager::VENVMANAGER
+        .find_venv(Some(name), None, method)
+        .await?;
+    if !venv.path.exists() {
+        eprintln!("{}", ERROR_VENV_NOT_EXISTS.red());
+        return None;
+    }
+    Some(venv)
+}
+
+pub async fn install(update: bool, version: Option<String>) {
+    let version: Option<Version> = match version.map(|v| v.parse()).transpose() {
+        Ok(v) => v,
+        Err(e) => processes::exit_with_error(&e),
+    };
+    let installed = uv::check().await;
+    match (uv::version().await, &version) {
+        (Some(current), Some(wanted)) if current == *wanted => {
+            println!(
+                "{}",
+                format!("Astral UV {} is already installed.", current).yellow()
+            );
+            return;
+        }
+        _ if installed && !update && version.is_none() => {
+            println!("{}", "Astral UV is already installed.".yellow());
+            return;
+        }
+        _ => {}
+    }
+    let result = if installed && uv::is_standalone() {
+        uv::self_update(version.as_ref()).await
     } else {
-        venvmanager::VENVMANAGER.print_venv_table(&mut venvs).await;
+        uv::install(io::stdin(), version.as_ref()).await
+    };
+    if let Err(e) = result {
+        eprintln!("{}", format!("Error installing Astral UV: {}", e).red());
+    }
+}
+
+pub async fn install_from(from: PathBuf, sha256: Option<String>, bin_dir: Option<PathBuf>) {
+    let bin_dir = bin_dir.unwrap_or_else(|| settings::Settings::get_settings().uv_bin_dir);
+    if let Err(e) = uv::install_from_archive(io::stdin(), &from, sha256.as_deref(), &bin_dir).await
+    {
+        processes::exit_with_error(&format!("Error installing Astral UV: {}", e));
+    }
+}
+
+pub async fn uninstall(clean: bool) {
+    if let Err(e) = uv::uninstall(io::stdin(), clean).await {
+        processes::exit_with_error(&format!("Error uninstalling Astral UV: {}", e));
     }
 }
 
+pub async fn list(columns: Vec<info::Column>, sort: info::SortKey, filters: Vec<info::Filter>) {
+    let venvs = venvmanager::VENVMANAGER.list().await;
+    if venvs.is_empty() {
+        println!("{}", "No virtual environments found".yellow());
+        return;
+    }
+    let required = info::required_columns(&columns, sort, &filters);
+    let mut infos = venvmanager::VENVMANAGER
+        .collect_info(venvs, &required)
+        .await;
+    infos.retain(|i| filters.iter().all(|f| f.matches(i)));
+    if infos.is_empty() {
+        println!("{}", "No virtual environments match the filter".yellow());
+        return;
+    }
+    info::sort_infos(&mut infos, sort);
+    venvmanager::VENVMANAGER.print_venv_table(&infos, &columns);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,7 +823,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_list() {
-        list().await;
+        list(vec![], info::SortKey::Name, vec![]).await;
     }
 
     #[tokio::test]
@@ -119,7 +832,28 @@ mod tests {
             println!("Skipping test in non-GitHub Actions environment");
             return;
         }
-        delete(Some("test_env".to_string()), None).await;
+        delete(vec!["test_env".to_string()]).await;
+    }
+
+    #[test]
+    fn test_apply_index_clears_wheelhouse() {
+        let mut venv = venv::Venv::new("index".to_string(), "".to_string(), vec![], false);
+        venv.wheelhouse = Some(PathBuf::from("/srv/wheels"));
+        venv.offline = true;
+        apply_index(&mut venv, IndexArgs::default()).unwrap();
+        assert!(venv.wheelhouse.is_some() && venv.offline);
+
+        let index = IndexArgs {
+            index_url: Some("https://pypi.corp.example/simple".to_string()),
+            ..IndexArgs::default()
+        };
+        apply_index(&mut venv, index).unwrap();
+        assert_eq!(venv.wheelhouse, None);
+        assert!(!venv.offline);
+        assert_eq!(
+            venv.index_url.as_deref(),
+            Some("https://pypi.corp.example/simple")
+        );
     }
 
     #[tokio::test]
@@ -2,8 +2,8 @@ mod cli;
 
 use crate::cli::run;
 use clap::Parser;
-use cli::clicmd::{Cli, Commands};
-use shared::settings;
+use cli::clicmd::{Cli, Commands, WheelhouseCommands};
+use shared::{info::Filter, settings};
 
 #[tokio::main]
 async fn main() {
@@ -22,6 +22,7 @@ async fn main() {
             packages,
             requirements,
             default,
+            index,
         }) => {
             run::create(
                 name_pos,
@@ -30,17 +31,100 @@ async fn main() {
                 packages,
                 requirements,
                 default,
+                index,
             )
             .await
         }
 
-        Some(Commands::Delete { name_pos, name }) => run::delete(name_pos, name).await,
+        Some(Commands::Add {
+            name,
+            packages,
+            index,
+        }) => run::add(name, packages, index).await,
+
+        Some(Commands::Delete { name_pos, name }) => {
+            run::delete(name.into_iter().chain(name_pos).collect()).await
+        }
+
+        Some(Commands::Rename { old, new }) => run::rename(old, new).await,
+
+        Some(Commands::Doctor { name, fix }) => run::doctor(name, fix).await,
+
+        Some(Commands::Prune {
+            older_than,
+            dry_run,
+        }) => run::prune(older_than, dry_run).await,
+
+        Some(Commands::Du) => run::du().await,
+
+        Some(Commands::List {
+            columns,
+            sort,
+            mut filter,
+            tag,
+        }) => {
+            filter.extend(tag.into_iter().map(Filter::Tag));
+            run::list(columns, sort, filter).await
+        }
 
-        Some(Commands::List) => run::list().await,
+        Some(Commands::Tag { name, tags }) => run::tag(name, tags).await,
 
-        Some(Commands::Install { update }) => run::install(update).await,
+        Some(Commands::Describe { name, description }) => run::describe(name, description).await,
 
-        Some(Commands::Uninstall) => run::uninstall().await,
+        Some(Commands::Info { name }) => run::info(name).await,
+
+        Some(Commands::Snapshot {
+            name,
+            label,
+            archive,
+        }) => run::snapshot(name, label, archive).await,
+
+        Some(Commands::Rollback { name, snapshot }) => run::rollback(name, snapshot).await,
+
+        Some(Commands::Diff {
+            name,
+            other,
+            requirements,
+            format,
+        }) => run::diff(name, other, requirements, format).await,
+
+        Some(Commands::Outdated { name, all, upgrade }) => run::outdated(name, all, upgrade).await,
+
+        Some(Commands::Audit { name, all, sync }) => run::audit(name, all, sync).await,
+
+        Some(Commands::Licenses { name, format }) => run::licenses(name, format).await,
+
+        Some(Commands::Tree {
+            name,
+            package,
+            reverse,
+            format,
+        }) => run::tree(name, package, reverse, format).await,
+
+        Some(Commands::Pack {
+            name,
+            output,
+            wheels,
+        }) => run::pack(name, output, wheels).await,
+
+        Some(Commands::Unpack { archive, name }) => run::unpack(archive, name).await,
+
+        Some(Commands::Wheelhouse {
+            command: WheelhouseCommands::Build { name, dir },
+        }) => run::wheelhouse_build(name, dir).await,
+
+        Some(Commands::Install {
+            from: Some(from),
+            sha256,
+            bin_dir,
+            ..
+        }) => run::install_from(from, sha256, bin_dir).await,
+
+        Some(Commands::Install {
+            update, version, ..
+        }) => run::install(update, version).await,
+
+        Some(Commands::Uninstall { clean }) => run::uninstall(clean).await,
 
         None => {
             println!("No command provided");
@@ -52,8 +136,11 @@ async fn main() {
 mod tests {
     use clap::Parser;
 
-    use crate::cli::clicmd::{Cli, Commands};
+    use crate::cli::clicmd::{Cli, Commands, WheelhouseCommands};
     use shared::constants::ERROR_VENV_NOT_EXISTS;
+    use shared::info::{Column, Filter, SortKey};
+    use shared::{diff::DiffFormat, licenses::LicenseFormat, tree::TreeFormat};
+    use std::path::PathBuf;
 
     #[test]
     fn test_cli_output_help() {
@@ -130,6 +217,28 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    fn test_cli_output_delete_traversal() {
+        assert_cli::Assert::main_binary()
+            .with_args(&["delete", "../.."])
+            .succeeds()
+            .and()
+            .stderr()
+            .contains("Invalid environment name")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_cli_output_create_invalid_name() {
+        assert_cli::Assert::main_binary()
+            .with_args(&["create", "/tmp/evil"])
+            .fails()
+            .and()
+            .stderr()
+            .contains("Invalid environment name")
+            .unwrap();
+    }
+
     #[test]
     fn test_cli_output_activate_name() {
         assert_cli::Assert::main_binary()
@@ -167,6 +276,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_delete_multiple() {
+        let args = Cli::try_parse_from([
+            "program", "delete", "tmp-*", "scratch1", "--name", "scratch2",
+        ])
+        .unwrap();
+
+        match args.commands {
+            Some(Commands::Delete { name_pos, name }) => {
+                assert_eq!(name_pos, vec!["tmp-*", "scratch1"]);
+                assert_eq!(name, vec!["scratch2"]);
+            }
+            _ => panic!("Expected Delete command"),
+        }
+    }
+
     #[test]
     fn test_create_command() {
         let args = Cli::try_parse_from([
@@ -199,11 +324,516 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_command_index() {
+        let args = Cli::try_parse_from([
+            "program",
+            "create",
+            "my-venv",
+            "--index-url",
+            "https://pypi.example.com/simple",
+            "--profile",
+            "corp",
+        ])
+        .unwrap();
+
+        match args.commands {
+            Some(Commands::Create { index, .. }) => {
+                assert_eq!(
+                    index.index_url.as_deref(),
+                    Some("https://pypi.example.com/simple")
+                );
+                assert_eq!(index.profile.as_deref(), Some("corp"));
+            }
+            _ => panic!("Expected Create command"),
+        }
+    }
+
+    #[test]
+    fn test_add_command() {
+        let args = Cli::try_parse_from([
+            "program",
+            "add",
+            "my-venv",
+            "requests",
+            "numpy",
+            "--profile",
+            "corp",
+        ])
+        .unwrap();
+
+        match args.commands {
+            Some(Commands::Add {
+                name,
+                packages,
+                index,
+            }) => {
+                assert_eq!(name, "my-venv");
+                assert_eq!(packages, vec!["requests", "numpy"]);
+                assert_eq!(index.profile.as_deref(), Some("corp"));
+                assert_eq!(index.index_url, None);
+            }
+            _ => panic!("Expected Add command"),
+        }
+        assert!(Cli::try_parse_from(["program", "add", "my-venv"]).is_err());
+    }
+
+    #[test]
+    fn test_rename_command() {
+        let args = Cli::try_parse_from(["program", "rename", "old-venv", "new-venv"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Rename { old, new }) => {
+                assert_eq!(old, "old-venv");
+                assert_eq!(new, "new-venv");
+            }
+            _ => panic!("Expected Rename command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_output_rename_missing() {
+        assert_cli::Assert::main_binary()
+            .with_args(&["rename", "myvenv", "othervenv"])
+            .succeeds()
+            .and()
+            .stderr()
+            .contains(ERROR_VENV_NOT_EXISTS)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_doctor_command() {
+        let args = Cli::try_parse_from(["program", "doctor", "my-venv", "--fix"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Doctor { name, fix }) => {
+                assert_eq!(name, Some("my-venv".to_string()));
+                assert!(fix);
+            }
+            _ => panic!("Expected Doctor command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_output_doctor_missing() {
+        assert_cli::Assert::main_binary()
+            .with_args(&["doctor", "myvenv"])
+            .succeeds()
+            .and()
+            .stderr()
+            .contains(ERROR_VENV_NOT_EXISTS)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_prune_command() {
+        let args =
+            Cli::try_parse_from(["program", "prune", "--older-than", "30d", "--dry-run"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Prune {
+                older_than,
+                dry_run,
+            }) => {
+                assert_eq!(older_than, "30d");
+                assert!(dry_run);
+            }
+            _ => panic!("Expected Prune command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_output_prune_invalid_age() {
+        assert_cli::Assert::main_binary()
+            .with_args(&["prune", "--older-than", "90"])
+            .fails()
+            .and()
+            .stderr()
+            .contains("Missing unit")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_install_uv_version_command() {
+        let args = Cli::try_parse_from(["program", "install-uv", "--version", "0.4.18"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Install {
+                update, version, ..
+            }) => {
+                assert!(!update);
+                assert_eq!(version.as_deref(), Some("0.4.18"));
+            }
+            _ => panic!("Expected Install command"),
+        }
+    }
+
+    #[test]
+    fn test_cli_output_install_uv_invalid_version() {
+        assert_cli::Assert::main_binary()
+            .with_args(&["install-uv", "--version", "latest; rm -rf /"])
+            .fails()
+            .and()
+            .stderr()
+            .contains("Invalid version")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_install_uv_from_command() {
+        let args = Cli::try_parse_from([
+            "program",
+            "install-uv",
+            "--from",
+            "uv.tar.gz",
+            "--sha256",
+            "abc",
+            "--bin-dir",
+            "/opt/bin",
+        ])
+        .unwrap();
+
+        match args.commands {
+            Some(Commands::Install {
+                from,
+                sha256,
+                bin_dir,
+                ..
+            }) => {
+                assert_eq!(from, Some(PathBuf::from("uv.tar.gz")));
+                assert_eq!(sha256.as_deref(), Some("abc"));
+                assert_eq!(bin_dir, Some(PathBuf::from("/opt/bin")));
+            }
+            _ => panic!("Expected Install command"),
+        }
+        assert!(Cli::try_parse_from(["program", "install-uv", "--sha256", "abc"]).is_err());
+        assert!(Cli::try_parse_from(["program", "install-uv", "--from", "a", "--update"]).is_err());
+    }
+
+    #[test]
+    fn test_uninstall_uv_command() {
+        let args = Cli::try_parse_from(["program", "uninstall-uv", "--clean"]).unwrap();
+
+        assert!(matches!(
+            args.commands,
+            Some(Commands::Uninstall { clean: true })
+        ));
+    }
+
+    #[test]
+    fn test_du_command() {
+        let args = Cli::try_parse_from(["program", "du"]).unwrap();
+
+        assert!(matches!(args.commands, Some(Commands::Du)));
+    }
+
     #[test]
     fn test_list_command() {
         let args = Cli::try_parse_from(["program", "list"]).unwrap();
 
-        assert!(matches!(args.commands, Some(Commands::List)));
+        assert!(matches!(args.commands, Some(Commands::List { .. })));
+    }
+
+    #[test]
+    fn test_list_command_options() {
+        let args = Cli::try_parse_from([
+            "program",
+            "list",
+            "--columns",
+            "size,path",
+            "--sort",
+            "size",
+            "--filter",
+            "python=3.11",
+        ])
+        .unwrap();
+
+        match args.commands {
+            Some(Commands::List {
+                columns,
+                sort,
+                filter,
+                ..
+            }) => {
+                assert_eq!(columns, vec![Column::Size, Column::Path]);
+                assert_eq!(sort, SortKey::Size);
+                assert_eq!(filter, vec![Filter::Python("3.11".to_string())]);
+            }
+            _ => panic!("Expected List command"),
+        }
+    }
+
+    #[test]
+    fn test_list_command_tag() {
+        let args = Cli::try_parse_from(["program", "list", "--tag", "gpu"]).unwrap();
+
+        match args.commands {
+            Some(Commands::List { tag, .. }) => assert_eq!(tag, vec!["gpu"]),
+            _ => panic!("Expected List command"),
+        }
+    }
+
+    #[test]
+    fn test_tag_command() {
+        let args =
+            Cli::try_parse_from(["program", "tag", "my-venv", "+gpu", "-old", "ml"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Tag { name, tags }) => {
+                assert_eq!(name, "my-venv");
+                assert_eq!(tags, vec!["+gpu", "-old", "ml"]);
+            }
+            _ => panic!("Expected Tag command"),
+        }
+    }
+
+    #[test]
+    fn test_describe_command() {
+        let args =
+            Cli::try_parse_from(["program", "describe", "my-venv", "Training jobs"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Describe { name, description }) => {
+                assert_eq!(name, "my-venv");
+                assert_eq!(description, "Training jobs");
+            }
+            _ => panic!("Expected Describe command"),
+        }
+    }
+
+    #[test]
+    fn test_create_offline_wheelhouse_command() {
+        let args = Cli::try_parse_from([
+            "program",
+            "create",
+            "my-venv",
+            "--offline",
+            "--wheelhouse",
+            "./wheels",
+            "-p",
+            "requests",
+        ])
+        .unwrap();
+
+        match args.commands {
+            Some(Commands::Create {
+                name_pos, index, ..
+            }) => {
+                assert_eq!(name_pos.as_deref(), Some("my-venv"));
+                assert!(index.offline);
+                assert_eq!(index.wheelhouse, Some(PathBuf::from("./wheels")));
+            }
+            _ => panic!("Expected Create command"),
+        }
+    }
+
+    #[test]
+    fn test_wheelhouse_build_command() {
+        let args =
+            Cli::try_parse_from(["program", "wheelhouse", "build", "my-venv", "./wheels"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Wheelhouse {
+                command: WheelhouseCommands::Build { name, dir },
+            }) => {
+                assert_eq!(name, "my-venv");
+                assert_eq!(dir, PathBuf::from("./wheels"));
+            }
+            _ => panic!("Expected Wheelhouse command"),
+        }
+        assert!(Cli::try_parse_from(["program", "wheelhouse", "build", "my-venv"]).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_command() {
+        let args =
+            Cli::try_parse_from(["program", "snapshot", "my-venv", "stable", "--archive"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Snapshot {
+                name,
+                label,
+                archive,
+            }) => {
+                assert_eq!(name, "my-venv");
+                assert_eq!(label.as_deref(), Some("stable"));
+                assert!(archive);
+            }
+            _ => panic!("Expected Snapshot command"),
+        }
+    }
+
+    #[test]
+    fn test_rollback_command() {
+        let args = Cli::try_parse_from(["program", "rollback", "my-venv"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Rollback { name, snapshot }) => {
+                assert_eq!(name, "my-venv");
+                assert_eq!(snapshot, None);
+            }
+            _ => panic!("Expected Rollback command"),
+        }
+    }
+
+    #[test]
+    fn test_pack_command() {
+        let args = Cli::try_parse_from([
+            "program",
+            "pack",
+            "my-venv",
+            "-o",
+            "env.tar.zst",
+            "--wheels",
+        ])
+        .unwrap();
+
+        match args.commands {
+            Some(Commands::Pack {
+                name,
+                output,
+                wheels,
+            }) => {
+                assert_eq!(name, "my-venv");
+                assert_eq!(output, Some(PathBuf::from("env.tar.zst")));
+                assert!(wheels);
+            }
+            _ => panic!("Expected Pack command"),
+        }
+    }
+
+    #[test]
+    fn test_unpack_command() {
+        let args =
+            Cli::try_parse_from(["program", "unpack", "env.tar.zst", "--name", "copy"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Unpack { archive, name }) => {
+                assert_eq!(archive, PathBuf::from("env.tar.zst"));
+                assert_eq!(name.as_deref(), Some("copy"));
+            }
+            _ => panic!("Expected Unpack command"),
+        }
+    }
+
+    #[test]
+    fn test_diff_command() {
+        let args = Cli::try_parse_from(["program", "diff", "a", "b", "--format", "json"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Diff {
+                name,
+                other,
+                requirements,
+                format,
+            }) => {
+                assert_eq!(name, "a");
+                assert_eq!(other.as_deref(), Some("b"));
+                assert_eq!(requirements, None);
+                assert_eq!(format, DiffFormat::Json);
+            }
+            _ => panic!("Expected Diff command"),
+        }
+        let args = Cli::try_parse_from(["program", "diff", "a", "-r", "req.txt"]).unwrap();
+        assert!(matches!(
+            args.commands,
+            Some(Commands::Diff {
+                requirements: Some(_),
+                ..
+            })
+        ));
+        assert!(Cli::try_parse_from(["program", "diff", "a"]).is_err());
+        assert!(Cli::try_parse_from(["program", "diff", "a", "b", "-r", "req.txt"]).is_err());
+    }
+
+    #[test]
+    fn test_outdated_command() {
+        let args = Cli::try_parse_from(["program", "outdated", "--all", "--upgrade"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Outdated { name, all, upgrade }) => {
+                assert_eq!(name, None);
+                assert!(all);
+                assert!(upgrade);
+            }
+            _ => panic!("Expected Outdated command"),
+        }
+        assert!(Cli::try_parse_from(["program", "outdated", "my-venv", "--all"]).is_err());
+    }
+
+    #[test]
+    fn test_audit_command() {
+        let args = Cli::try_parse_from(["program", "audit", "my-venv", "--sync"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Audit { name, all, sync }) => {
+                assert_eq!(name, Some("my-venv".to_string()));
+                assert!(!all);
+                assert!(sync);
+            }
+            _ => panic!("Expected Audit command"),
+        }
+        assert!(Cli::try_parse_from(["program", "audit", "my-venv", "--all"]).is_err());
+    }
+
+    #[test]
+    fn test_licenses_command() {
+        let args = Cli::try_parse_from(["program", "licenses", "my-venv", "-f", "csv"]).unwrap();
+
+        match args.commands {
+            Some(Commands::Licenses { name, format }) => {
+                assert_eq!(name, "my-venv");
+                assert_eq!(format, LicenseFormat::Csv);
+            }
+            _ => panic!("Expected Licenses command"),
+        }
+        assert!(Cli::try_parse_from(["program", "licenses", "my-venv", "-f", "xml"]).is_err());
+    }
+
+    #[test]
+    fn test_tree_command() {
+        let args = Cli::try_parse_from([
+            "program",
+            "tree",
+            "my-venv",
+            "--reverse",
+            "idna",
+            "-f",
+            "dot",
+        ])
+        .unwrap();
+
+        match args.commands {
+            Some(Commands::Tree {
+                name,
+                package,
+                reverse,
+                format,
+            }) => {
+                assert_eq!(name, "my-venv");
+                assert_eq!(package, None);
+                assert_eq!(reverse, Some("idna".to_string()));
+                assert_eq!(format, TreeFormat::Dot);
+            }
+            _ => panic!("Expected Tree command"),
+        }
+        assert!(Cli::try_parse_from([
+            "program",
+            "tree",
+            "my-venv",
+            "requests",
+            "--reverse",
+            "idna"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_list_command_invalid_column() {
+        let result = Cli::try_parse_from(["program", "list", "--columns", "colour"]);
+
+        assert!(result.is_err());
     }
 
     #[test]
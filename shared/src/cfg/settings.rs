@@ -1,21 +1,156 @@
+use crate::constants::{
+    ADVISORY_DIR, OSV_PYPI_URL, PYPILOT_UV_ENV, STATE_DIR, UV_CACHE_DIR_ENV, UV_CMD,
+    UV_EXTRA_INDEX_URL_ENV, UV_INDEX_URL_ENV, UV_PYTHON_INSTALL_DIR_ENV,
+};
 use config::{Config, File, FileFormat};
 use once_cell::sync::Lazy;
-use std::{
-    env,
-    path::{Path, PathBuf},
-    sync::Mutex,
-};
+use std::{collections::BTreeMap, env, path::PathBuf, sync::Mutex};
 
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct Settings {
-    #[serde(default = "default_venv_path")]
-    pub venvs_path: String,
+    #[serde(default = "default_venv_path", deserialize_with = "deserialize_path")]
+    pub venvs_path: PathBuf,
     #[serde(default)]
     pub default_pkgs: Vec<String>,
+    #[serde(default = "default_uv_bin_dir", deserialize_with = "deserialize_path")]
+    pub uv_bin_dir: PathBuf,
+    #[serde(default, deserialize_with = "deserialize_optional_path")]
+    pub uv_path: Option<PathBuf>,
+    #[serde(default, deserialize_with = "deserialize_optional_path")]
+    pub uv_cache_dir: Option<PathBuf>,
+    #[serde(default, deserialize_with = "deserialize_optional_path")]
+    pub uv_python_install_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub uv_env: BTreeMap<String, String>,
+    #[serde(flatten)]
+    pub index: IndexSettings,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, IndexSettings>,
+    #[serde(default, deserialize_with = "deserialize_optional_path")]
+    pub advisory_db: Option<PathBuf>,
+    #[serde(default)]
+    pub advisory_url: Option<String>,
+    #[serde(default)]
+    pub licenses: LicensePolicy,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+pub struct LicensePolicy {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
 }
 
-fn default_venv_path() -> String {
-    String::from("~/pymngr/venvs")
+impl LicensePolicy {
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+pub struct IndexSettings {
+    #[serde(default)]
+    pub index_url: Option<String>,
+    #[serde(default)]
+    pub extra_index_urls: Vec<String>,
+    #[serde(default)]
+    pub find_links: Vec<String>,
+    #[serde(default)]
+    pub trusted_hosts: Vec<String>,
+    #[serde(default)]
+    pub credentials_env: Option<String>,
+    #[serde(default)]
+    pub no_index: bool,
+    #[serde(default)]
+    pub offline: bool,
+}
+
+impl IndexSettings {
+    pub fn merge(&self, other: &IndexSettings) -> IndexSettings {
+        let pick = |own: &Vec<String>, other: &Vec<String>| {
+            if other.is_empty() {
+                own.clone()
+            } else {
+                other.clone()
+            }
+        };
+        IndexSettings {
+            index_url: other.index_url.clone().or_else(|| self.index_url.clone()),
+            extra_index_urls: pick(&self.extra_index_urls, &other.extra_index_urls),
+            find_links: pick(&self.find_links, &other.find_links),
+            trusted_hosts: pick(&self.trusted_hosts, &other.trusted_hosts),
+            credentials_env: other
+                .credentials_env
+                .clone()
+                .or_else(|| self.credentials_env.clone()),
+            no_index: self.no_index || other.no_index,
+            offline: self.offline || other.offline,
+        }
+    }
+
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.offline {
+            args.push("--offline".to_string());
+        }
+        if self.no_index {
+            args.push("--no-index".to_string());
+        }
+        for link in &self.find_links {
+            args.extend(["--find-links".to_string(), link.clone()]);
+        }
+        for host in &self.trusted_hosts {
+            args.extend(["--allow-insecure-host".to_string(), host.clone()]);
+        }
+        args
+    }
+
+    pub fn env(&self) -> Vec<(String, String)> {
+        let mut env = Vec::new();
+        if let Some(url) = &self.index_url {
+            env.push((UV_INDEX_URL_ENV.to_string(), url.clone()));
+        }
+        if !self.extra_index_urls.is_empty() {
+            env.push((
+                UV_EXTRA_INDEX_URL_ENV.to_string(),
+                self.extra_index_urls.join(" "),
+            ));
+        }
+        env
+    }
+}
+
+fn default_venv_path() -> PathBuf {
+    PathBuf::from(shellexpand::tilde("~/pymngr/venvs").as_ref())
+}
+
+fn default_uv_bin_dir() -> PathBuf {
+    PathBuf::from(shellexpand::tilde("~/.local/bin").as_ref())
+}
+
+fn deserialize_path<'de, D>(deserializer: D) -> Result<PathBuf, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = <String as serde::Deserialize>::deserialize(deserializer)?;
+    expand_path(&raw).map_err(serde::de::Error::custom)
+}
+
+fn deserialize_optional_path<'de, D>(deserializer: D) -> Result<Option<PathBuf>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = <Option<String> as serde::Deserialize>::deserialize(deserializer)?;
+    raw.filter(|p| !p.is_empty())
+        .map(|p| expand_path(&p).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+pub fn expand_path(path: &str) -> Result<PathBuf, String> {
+    shellexpand::full(path)
+        .map(|p| PathBuf::from(p.as_ref()))
+        .map_err(|e| format!("Could not expand path '{}': {}", path, e))
 }
 
 static SETTINGS: Lazy<Mutex<Settings>> = Lazy::new(|| Mutex::new(Settings::default()));
@@ -25,6 +160,16 @@ impl Default for Settings {
         Settings {
             venvs_path: default_venv_path(),
             default_pkgs: Vec::new(),
+            uv_bin_dir: default_uv_bin_dir(),
+            uv_path: None,
+            uv_cache_dir: None,
+            uv_python_install_dir: None,
+            uv_env: BTreeMap::new(),
+            index: IndexSettings::default(),
+            profiles: BTreeMap::new(),
+            advisory_db: None,
+            advisory_url: None,
+            licenses: LicensePolicy::default(),
         }
     }
 }
@@ -53,19 +198,88 @@ impl Settings {
         *settings_lock = new_settings;
     }
 
+    pub fn state_dir(&self) -> PathBuf {
+        self.venvs_path.join(STATE_DIR)
+    }
+
+    pub fn advisory_db(&self) -> PathBuf {
+        self.advisory_db
+            .clone()
+            .unwrap_or_else(|| self.state_dir().join(ADVISORY_DIR))
+    }
+
+    pub fn advisory_url(&self) -> String {
+        self.advisory_url
+            .clone()
+            .unwrap_or_else(|| OSV_PYPI_URL.to_string())
+    }
+
+    pub fn uv_cmd(&self) -> String {
+        if let Some(path) = env::var(PYPILOT_UV_ENV).ok().filter(|p| !p.is_empty()) {
+            return expand_path(&path)
+                .map(|p| p.display().to_string())
+                .unwrap_or(path);
+        }
+        match &self.uv_path {
+            Some(path) => path.display().to_string(),
+            None => UV_CMD.to_string(),
+        }
+    }
+
+    pub fn uv_env(&self) -> Vec<(String, String)> {
+        let mut env: Vec<(String, String)> = self
+            .uv_env
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        if let Some(dir) = &self.uv_cache_dir {
+            env.push((UV_CACHE_DIR_ENV.to_string(), dir.display().to_string()));
+        }
+        if let Some(dir) = &self.uv_python_install_dir {
+            env.push((
+                UV_PYTHON_INSTALL_DIR_ENV.to_string(),
+                dir.display().to_string(),
+            ));
+        }
+        env
+    }
+
+    pub fn index_settings(
+        &self,
+        profile: Option<&str>,
+        index_url: Option<&str>,
+    ) -> Result<IndexSettings, String> {
+        let mut index = self.index.clone();
+        if let Some(name) = profile {
+            let profile = self.profiles.get(name).ok_or_else(|| {
+                let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+                format!(
+                    "Unknown index profile '{}', available profiles: {}",
+                    name,
+                    if known.is_empty() {
+                        "none".to_string()
+                    } else {
+                        known.join(", ")
+                    }
+                )
+            })?;
+            index = index.merge(profile);
+        }
+        if let Some(url) = index_url {
+            index.index_url = Some(url.to_string());
+        }
+        Ok(index)
+    }
+
     pub fn get_settings() -> Settings {
         let settings_lock = SETTINGS.lock().expect("Failed to lock settings");
         settings_lock.clone()
     }
 
     fn validate_venv_path(&self) {
-        let mut path = self.venvs_path.clone();
-        if path.starts_with("~") {
-            path = shellexpand::tilde(&path).to_string();
-        }
-        if !Path::new(&path).exists() {
-            println!("Creating venvs folder: {}", path);
-            std::fs::create_dir_all(&path).expect("Failed to create venvs folder");
+        if !self.venvs_path.exists() {
+            println!("Creating venvs folder: {}", self.venvs_path.display());
+            std::fs::create_dir_all(&self.venvs_path).expect("Failed to create venvs folder");
         }
     }
 
@@ -88,32 +302,131 @@ impl Settings {
 mod tests {
     use super::*;
 
+    fn tilde(path: &str) -> PathBuf {
+        PathBuf::from(shellexpand::tilde(path).as_ref())
+    }
+
     #[test]
     fn test_default_venv_path() {
         let settings = Settings::default();
-        assert_eq!(settings.venvs_path, "~/pymngr/venvs");
+        assert_eq!(settings.venvs_path, tilde("~/pymngr/venvs"));
     }
 
     #[test]
     fn test_validate_venv_path() {
         let settings = Settings {
-            venvs_path: "~/pymngr/venvs".to_string(),
+            venvs_path: tilde("~/pymngr/venvs"),
             default_pkgs: vec![],
+            ..Settings::default()
         };
         settings.validate_venv_path();
-        let expected_path = shellexpand::tilde("~/pymngr/venvs").to_string();
-        assert!(Path::new(&expected_path).exists());
+        assert!(tilde("~/pymngr/venvs").exists());
     }
 
     #[test]
     fn test_get_settings() {
         let settings = Settings {
-            venvs_path: "~/pymngr/venvs".to_string(),
+            venvs_path: tilde("~/pymngr/venvs"),
             default_pkgs: vec![],
+            ..Settings::default()
         };
         let settings_lock = Mutex::new(settings);
         let settings = settings_lock.lock().unwrap();
-        assert_eq!(settings.venvs_path, "~/pymngr/venvs");
+        assert_eq!(settings.venvs_path, tilde("~/pymngr/venvs"));
+    }
+
+    #[test]
+    fn test_default_uv_bin_dir() {
+        let settings = Settings::default();
+        assert_eq!(settings.uv_bin_dir, tilde("~/.local/bin"));
+        assert_eq!(settings.state_dir(), tilde("~/pymngr/venvs/.pypilot"));
+    }
+
+    #[test]
+    fn test_uv_env() {
+        let settings: Settings = toml::from_str(
+            r#"
+            uv_cache_dir = "/tmp/uv-cache"
+            uv_python_install_dir = "/opt/pythons"
+            index_url = "https://pypi.example.com/simple"
+            extra_index_urls = ["https://a.example.com/simple", "https://b.example.com/simple"]
+
+            [uv_env]
+            UV_NATIVE_TLS = "1"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.uv_env(),
+            vec![
+                ("UV_NATIVE_TLS".to_string(), "1".to_string()),
+                ("UV_CACHE_DIR".to_string(), "/tmp/uv-cache".to_string()),
+                (
+                    "UV_PYTHON_INSTALL_DIR".to_string(),
+                    "/opt/pythons".to_string()
+                ),
+            ]
+        );
+        assert!(Settings::default().uv_env().is_empty());
+    }
+
+    #[test]
+    fn test_index_settings_profiles() {
+        let settings: Settings = toml::from_str(
+            r#"
+            index_url = "https://pypi.example.com/simple"
+            find_links = ["/srv/wheels"]
+
+            [profiles.corp]
+            index_url = "https://corp.example.com/simple"
+            trusted_hosts = ["corp.example.com"]
+            credentials_env = "CORP_PYPI_CREDENTIALS"
+            "#,
+        )
+        .unwrap();
+
+        let global = settings.index_settings(None, None).unwrap();
+        assert_eq!(
+            global.index_url.as_deref(),
+            Some("https://pypi.example.com/simple")
+        );
+
+        let corp = settings.index_settings(Some("corp"), None).unwrap();
+        assert_eq!(
+            corp.index_url.as_deref(),
+            Some("https://corp.example.com/simple")
+        );
+        assert_eq!(corp.find_links, vec!["/srv/wheels"]);
+        assert_eq!(
+            corp.args(),
+            vec![
+                "--find-links",
+                "/srv/wheels",
+                "--allow-insecure-host",
+                "corp.example.com"
+            ]
+        );
+        assert_eq!(
+            corp.credentials_env.as_deref(),
+            Some("CORP_PYPI_CREDENTIALS")
+        );
+
+        let cli = settings
+            .index_settings(Some("corp"), Some("http://localhost/simple"))
+            .unwrap();
+        assert_eq!(cli.index_url.as_deref(), Some("http://localhost/simple"));
+
+        let err = settings.index_settings(Some("missing"), None).unwrap_err();
+        assert!(err.contains("available profiles: corp"));
+    }
+
+    #[test]
+    fn test_uv_cmd() {
+        let settings: Settings = toml::from_str(r#"uv_path = "/opt/corp/uv""#).unwrap();
+        if env::var(PYPILOT_UV_ENV).is_err() {
+            assert_eq!(settings.uv_cmd(), "/opt/corp/uv");
+            assert_eq!(Settings::default().uv_cmd(), "uv");
+        }
     }
 
     #[test]
@@ -127,7 +440,7 @@ mod tests {
     async fn test_init() {
         Settings::init().await;
         let settings = Settings::get_settings();
-        assert_eq!(settings.venvs_path, "~/pymngr/venvs");
+        assert_eq!(settings.venvs_path, tilde("~/pymngr/venvs"));
     }
 
     #[test]
@@ -138,10 +451,45 @@ mod tests {
         "#;
 
         let settings: Settings = toml::from_str(toml_str).unwrap();
-        assert_eq!(settings.venvs_path, "~/custom/venvs");
+        assert_eq!(settings.venvs_path, tilde("~/custom/venvs"));
         assert_eq!(settings.default_pkgs, vec!["numpy", "pandas"]);
     }
 
+    #[test]
+    fn test_license_policy() {
+        let toml_str = r#"
+            [licenses]
+            allow = ["MIT", "BSD-*"]
+        "#;
+
+        let settings: Settings = toml::from_str(toml_str).unwrap();
+        assert_eq!(settings.licenses.allow, vec!["MIT", "BSD-*"]);
+        assert!(settings.licenses.deny.is_empty());
+        assert!(!settings.licenses.is_empty());
+        assert!(Settings::default().licenses.is_empty());
+    }
+
+    #[test]
+    fn test_settings_deserialize_env_var() {
+        env::set_var("PYPILOT_TEST_VENVS_ROOT", "/opt/pypilot");
+        let toml_str = r#"
+            venvs_path = "$PYPILOT_TEST_VENVS_ROOT/venvs"
+        "#;
+
+        let settings: Settings = toml::from_str(toml_str).unwrap();
+        assert_eq!(settings.venvs_path, PathBuf::from("/opt/pypilot/venvs"));
+    }
+
+    #[test]
+    fn test_settings_deserialize_unknown_env_var() {
+        let toml_str = r#"
+            venvs_path = "$PYPILOT_SURELY_NOT_SET/venvs"
+        "#;
+
+        let result: Result<Settings, _> = toml::from_str(toml_str);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_settings_deserialize_missing_fields() {
         let toml_str = r#"
@@ -149,7 +497,7 @@ mod tests {
         "#;
 
         let settings: Settings = toml::from_str(toml_str).unwrap();
-        assert_eq!(settings.venvs_path, "~/pymngr/venvs");
+        assert_eq!(settings.venvs_path, tilde("~/pymngr/venvs"));
         assert_eq!(settings.default_pkgs, vec!["requests"]);
     }
 
@@ -1,13 +1,44 @@
-use crate::utility::constants::{POWERSHELL_CMD, PWSH_CMD};
+use crate::{
+    settings::Settings,
+    utility::constants::{POWERSHELL_CMD, PWSH_CMD, UV_CMD},
+};
 use colored::Colorize;
-use std::process::{Command as StdCommand, Stdio};
+use std::{
+    ffi::OsStr,
+    path::Path,
+    process::{Command as StdCommand, Stdio},
+};
 use tokio::{
     io::{AsyncBufReadExt, BufReader},
     process::{Child, Command},
 };
 
-pub fn create_child_cmd(cmd: &str, args: &[&str], run: &str) -> Child {
+fn uv_env(program: &str) -> Vec<(String, String)> {
+    let settings = Settings::get_settings();
+    let is_uv = program == settings.uv_cmd()
+        || Path::new(program)
+            .file_stem()
+            .is_some_and(|stem| stem == UV_CMD);
+    if is_uv {
+        settings.uv_env()
+    } else {
+        Vec::new()
+    }
+}
+
+pub fn create_child_cmd<S: AsRef<OsStr>>(cmd: &str, args: &[S], run: &str) -> Child {
+    create_child_cmd_with_env(cmd, args, run, &[])
+}
+
+pub fn create_child_cmd_with_env<S: AsRef<OsStr>>(
+    cmd: &str,
+    args: &[S],
+    run: &str,
+    env: &[(String, String)],
+) -> Child {
+    let uv_env = uv_env(cmd);
     let mut cmd = Command::new(cmd);
+    cmd.envs(uv_env).envs(env.iter().map(|(k, v)| (k, v)));
     if !run.is_empty() {
         cmd.arg(run);
     }
@@ -18,6 +49,34 @@ pub fn create_child_cmd(cmd: &str, args: &[&str], run: &str) -> Child {
         .expect("Failed to execute command")
 }
 
+pub async fn capture_output<S: AsRef<OsStr>>(cmd: &str, args: &[S]) -> Result<String, String> {
+    capture_output_with_env(cmd, args, &[]).await
+}
+
+pub async fn capture_output_with_env<S: AsRef<OsStr>>(
+    cmd: &str,
+    args: &[S],
+    env: &[(String, String)],
+) -> Result<String, String> {
+    let output = Command::new(cmd)
+        .envs(uv_env(cmd))
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute {}: {}", cmd, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} exited with status {}: {}",
+            cmd,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 pub fn activate_venv_shell(cmd: &str, args: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(unix)]
     {
@@ -163,6 +222,27 @@ mod tests {
         }
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_capture_output() {
+        let output = capture_output("sh", &["-c", "echo Hello"]).await;
+        assert_eq!(output.unwrap().trim(), "Hello");
+        let failed = capture_output("sh", &["-c", "echo oops >&2; exit 3"]).await;
+        assert!(failed.unwrap_err().contains("oops"));
+        assert!(capture_output("pypilot-missing-binary", &["x"])
+            .await
+            .is_err());
+        let env = [("PYPILOT_TEST_VALUE".to_string(), "42".to_string())];
+        let output = capture_output_with_env("sh", &["-c", "echo $PYPILOT_TEST_VALUE"], &env).await;
+        assert_eq!(output.unwrap().trim(), "42");
+    }
+
+    #[test]
+    fn test_uv_env_only_for_uv() {
+        assert!(uv_env("ls").is_empty());
+        assert_eq!(uv_env("/opt/bin/uv"), Settings::get_settings().uv_env());
+    }
+
     #[tokio::test]
     async fn test_create_child_cmd_run() {
         if cfg!(target_os = "windows") {
@@ -5,5 +5,8 @@ mod venvcore;
 
 pub use cfg::settings;
 pub use core::*;
-pub use utility::{constants, utils};
-pub use venvcore::{uv, venv, venvmanager};
+pub use utility::{constants, marker, requirement, utils, validation, version};
+pub use venvcore::{
+    audit, credentials, diff, doctor, info, licenses, metadata, outdated, pack, picker,
+    site_packages, snapshot, tree, usage, uv, uvarchive, venv, venvmanager, wheelhouse,
+};
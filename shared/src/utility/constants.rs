@@ -12,7 +12,27 @@ pub const UNIX_PYTHON3_EXEC: &str = "bin/python3";
 pub const UNIX_PYTHON_EXEC: &str = "bin/python";
 pub const UV_UNIX_INSTALL_ARGS: &[&str] =
     &["-c", "curl -LsSf https://astral.sh/uv/install.sh | sh"];
-pub const UV_UNIX_UNINSTALL_ARGS: &[&str] = &["-c", "rm ~/.local/bin/uv ~/.local/bin/uvx"];
+pub const UV_UNIX_INSTALL_URL: &str = "https://astral.sh/uv";
+
+// Shared constants
+pub const UV_CMD: &str = "uv";
+pub const CURL_CMD: &str = "curl";
+pub const PYPILOT_UV_ENV: &str = "PYPILOT_UV";
+pub const UV_CACHE_DIR_ENV: &str = "UV_CACHE_DIR";
+pub const UV_PYTHON_INSTALL_DIR_ENV: &str = "UV_PYTHON_INSTALL_DIR";
+pub const UV_INDEX_URL_ENV: &str = "UV_INDEX_URL";
+pub const UV_EXTRA_INDEX_URL_ENV: &str = "UV_EXTRA_INDEX_URL";
+pub const UV_MIN_VERSION: &str = "0.4.0";
+pub const UV_RECEIPT_FILE: &str = "uv-receipt.json";
+pub const METADATA_FILE: &str = ".pypilot.toml";
+pub const DIST_INFO_METADATA: &str = "METADATA";
+pub const STATE_DIR: &str = ".pypilot";
+pub const UV_INSTALL_RECORD_FILE: &str = "uv-install.toml";
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+pub const DOCTOR_BACKUP_DIR: &str = "doctor-backups";
+pub const MAX_AUTO_SNAPSHOTS: usize = 10;
+pub const ADVISORY_DIR: &str = "advisories";
+pub const OSV_PYPI_URL: &str = "https://osv-vulnerabilities.storage.googleapis.com/PyPI/all.zip";
 
 // Error messages
 pub const ERROR_CREATING_VENV: &str = "Error creating virtual environment";
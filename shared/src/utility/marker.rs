@@ -0,0 +1,263 @@
+use crate::version::{Version, VersionSpec};
+use std::collections::HashMap;
+
+const VERSION_VARIABLES: &[&str] = &[
+    "python_version",
+    "python_full_version",
+    "implementation_version",
+];
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarkerEnv {
+    values: HashMap<String, String>,
+}
+
+impl MarkerEnv {
+    pub fn new(python_full_version: Option<&str>, implementation: Option<&str>) -> Self {
+        let (sys_platform, os_name, platform_system) = if cfg!(target_os = "windows") {
+            ("win32", "nt", "Windows")
+        } else if cfg!(target_os = "macos") {
+            ("darwin", "posix", "Darwin")
+        } else if cfg!(target_os = "linux") {
+            ("linux", "posix", "Linux")
+        } else {
+            (std::env::consts::OS, "posix", std::env::consts::OS)
+        };
+        let platform_machine = match (std::env::consts::OS, std::env::consts::ARCH) {
+            ("windows", "x86_64") => "AMD64",
+            ("macos", "aarch64") => "arm64",
+            (_, arch) => arch,
+        };
+        let implementation = implementation.unwrap_or("CPython");
+        let mut values: HashMap<String, String> = [
+            ("sys_platform", sys_platform),
+            ("os_name", os_name),
+            ("platform_system", platform_system),
+            ("platform_machine", platform_machine),
+            ("platform_python_implementation", implementation),
+            ("extra", ""),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        values.insert(
+            "implementation_name".to_string(),
+            implementation.to_lowercase(),
+        );
+        if let Some(full) = python_full_version.filter(|v| v.parse::<Version>().is_ok()) {
+            let short: Vec<&str> = full.split('.').take(2).collect();
+            values.insert("python_version".to_string(), short.join("."));
+            values.insert("python_full_version".to_string(), full.to_string());
+            values.insert("implementation_version".to_string(), full.to_string());
+        }
+        MarkerEnv { values }
+    }
+
+    pub fn set(&mut self, variable: &str, value: &str) {
+        self.values.insert(variable.to_string(), value.to_string());
+    }
+
+    pub fn evaluate(&self, marker: &str) -> Option<bool> {
+        let tokens = tokenize(marker)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            pos: 0,
+            env: self,
+        };
+        let result = parser.or()?;
+        (parser.pos == tokens.len()).then_some(result)?
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Str(String),
+    Word(String),
+    Op(&'static str),
+}
+
+fn tokenize(marker: &str) -> Option<Vec<Token>> {
+    const OPS: [&str; 8] = ["===", "~=", "==", "!=", "<=", ">=", "<", ">"];
+    let mut tokens = Vec::new();
+    let mut rest = marker.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if c == '(' || c == ')' {
+            tokens.push(if c == '(' { Token::Open } else { Token::Close });
+            rest = &rest[1..];
+        } else if c == '\'' || c == '"' {
+            let end = rest[1..].find(c)? + 1;
+            tokens.push(Token::Str(rest[1..end].to_string()));
+            rest = &rest[end + 1..];
+        } else if let Some(op) = OPS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            rest = &rest[op.len()..];
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.')))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Word(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else {
+            return None;
+        }
+        rest = rest.trim_start();
+    }
+    Some(tokens)
+}
+
+enum Operand {
+    Literal(String),
+    Variable(String),
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    env: &'a MarkerEnv,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos)?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    fn keyword(&mut self, word: &str) -> bool {
+        let found = matches!(self.tokens.get(self.pos), Some(Token::Word(w)) if w == word);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    // The outer Option is a syntax error, the inner one a condition that cannot be evaluated.
+    fn or(&mut self) -> Option<Option<bool>> {
+        let mut result = self.and()?;
+        while self.keyword("or") {
+            let right = self.and()?;
+            result = match (result, right) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            };
+        }
+        Some(result)
+    }
+
+    fn and(&mut self) -> Option<Option<bool>> {
+        let mut result = self.atom()?;
+        while self.keyword("and") {
+            let right = self.atom()?;
+            result = match (result, right) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            };
+        }
+        Some(result)
+    }
+
+    fn atom(&mut self) -> Option<Option<bool>> {
+        if self.tokens.get(self.pos) == Some(&Token::Open) {
+            self.pos += 1;
+            let result = self.or()?;
+            return (self.next()? == &Token::Close).then_some(result);
+        }
+        let left = self.operand()?;
+        let op = match self.next()?.clone() {
+            Token::Op(op) => op,
+            Token::Word(w) if w == "in" => "in",
+            Token::Word(w) if w == "not" && self.keyword("in") => "not in",
+            _ => return None,
+        };
+        let right = self.operand()?;
+        Some(self.compare(left, op, right))
+    }
+
+    fn operand(&mut self) -> Option<Operand> {
+        match self.next()? {
+            Token::Str(s) => Some(Operand::Literal(s.clone())),
+            Token::Word(w) if !matches!(w.as_str(), "and" | "or" | "in" | "not") => {
+                Some(Operand::Variable(w.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    fn value(&self, operand: &Operand) -> Option<String> {
+        match operand {
+            Operand::Literal(s) => Some(s.clone()),
+            Operand::Variable(name) => self.env.values.get(name).cloned(),
+        }
+    }
+
+    fn compare(&self, left: Operand, op: &str, right: Operand) -> Option<bool> {
+        let lhs = self.value(&left)?;
+        let rhs = self.value(&right)?;
+        let is_version = |o: &Operand| matches!(o, Operand::Variable(v) if VERSION_VARIABLES.contains(&v.as_str()));
+        match op {
+            "in" => return Some(rhs.contains(&lhs)),
+            "not in" => return Some(!rhs.contains(&lhs)),
+            "===" => return Some(lhs == rhs),
+            _ => {}
+        }
+        if is_version(&left) || is_version(&right) {
+            let (value, op, bound) = if is_version(&left) {
+                (lhs, op, rhs)
+            } else {
+                let flipped = match op {
+                    "<" => ">",
+                    ">" => "<",
+                    "<=" => ">=",
+                    ">=" => "<=",
+                    "==" | "!=" => op,
+                    _ => return None,
+                };
+                (rhs, flipped, lhs)
+            };
+            let spec: VersionSpec = format!("{}{}", op, bound).parse().ok()?;
+            return Some(spec.contains(&value.parse().ok()?));
+        }
+        match op {
+            "==" => Some(lhs == rhs),
+            "!=" => Some(lhs != rhs),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let mut env = MarkerEnv::new(Some("3.11.4"), None);
+        env.set("sys_platform", "linux");
+        assert_eq!(env.evaluate("python_version >= '3.8'"), Some(true));
+        assert_eq!(env.evaluate("python_version < \"3.10\""), Some(false));
+        assert_eq!(env.evaluate("'3.12' <= python_version"), Some(false));
+        assert_eq!(env.evaluate("python_full_version == '3.11.*'"), Some(true));
+        assert_eq!(env.evaluate("sys_platform == 'win32'"), Some(false));
+        assert_eq!(
+            env.evaluate("sys_platform == 'win32' or (python_version > '3.9' and os_name != 'nt')"),
+            Some(env.evaluate("os_name != 'nt'").unwrap())
+        );
+        assert_eq!(env.evaluate("'linux' in sys_platform"), Some(true));
+        assert_eq!(env.evaluate("extra == 'socks'"), Some(false));
+        assert_eq!(env.evaluate("platform_release >= '5'"), None);
+        assert_eq!(
+            env.evaluate("platform_release >= '5' and sys_platform == 'win32'"),
+            Some(false)
+        );
+        assert_eq!(env.evaluate("python_version >"), None);
+        assert_eq!(env.evaluate("(python_version > '3'"), None);
+        assert_eq!(
+            MarkerEnv::new(None, None).evaluate("python_version > '3'"),
+            None
+        );
+    }
+}
@@ -1,2 +1,6 @@
 pub mod constants;
+pub mod marker;
+pub mod requirement;
 pub mod utils;
+pub mod validation;
+pub mod version;
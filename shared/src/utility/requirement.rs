@@ -0,0 +1,124 @@
+use crate::version::VersionSpec;
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    pub name: String,
+    pub extras: Vec<String>,
+    pub spec: VersionSpec,
+    pub marker: Option<String>,
+}
+
+pub fn normalize_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for c in name.trim().chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !normalized.ends_with('-') {
+                normalized.push('-');
+            }
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+        }
+    }
+    normalized
+}
+
+impl Requirement {
+    pub fn key(&self) -> String {
+        normalize_name(&self.name)
+    }
+}
+
+impl FromStr for Requirement {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid requirement '{}'", s);
+        let (requirement, marker) = match s.split_once(';') {
+            Some((r, m)) => (r.trim(), Some(m.trim().to_string())),
+            None => (s.trim(), None),
+        };
+        let name_end = requirement
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+            .unwrap_or(requirement.len());
+        let name = &requirement[..name_end];
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        let mut rest = requirement[name_end..].trim_start();
+        let mut extras = Vec::new();
+        if let Some(after) = rest.strip_prefix('[') {
+            let (list, after) = after.split_once(']').ok_or_else(invalid)?;
+            extras = list
+                .split(',')
+                .map(|e| e.trim().to_string())
+                .filter(|e| !e.is_empty())
+                .collect();
+            rest = after.trim_start();
+        }
+        let spec = if rest.starts_with('@') {
+            VersionSpec::default()
+        } else {
+            rest.trim_start_matches('(')
+                .trim_end_matches(')')
+                .parse()
+                .map_err(|e| format!("{}: {}", invalid(), e))?
+        };
+        Ok(Requirement {
+            name: name.to_string(),
+            extras,
+            spec,
+            marker,
+        })
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.extras.is_empty() {
+            write!(f, "[{}]", self.extras.join(","))?;
+        }
+        write!(f, "{}", self.spec)?;
+        if let Some(marker) = &self.marker {
+            write!(f, "; {}", marker)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("Typing_Extensions"), "typing-extensions");
+        assert_eq!(normalize_name("zope.interface"), "zope-interface");
+        assert_eq!(normalize_name("a__-b"), "a-b");
+    }
+
+    #[test]
+    fn test_parse_requirement() {
+        let req: Requirement = "requests[socks, security] (>=2.0,<3) ; python_version >= '3.8'"
+            .parse()
+            .unwrap();
+        assert_eq!(req.name, "requests");
+        assert_eq!(req.extras, vec!["socks", "security"]);
+        assert_eq!(req.spec.to_string(), ">=2.0,<3");
+        assert_eq!(req.marker.as_deref(), Some("python_version >= '3.8'"));
+        assert_eq!(
+            req.to_string(),
+            "requests[socks,security]>=2.0,<3; python_version >= '3.8'"
+        );
+
+        let pinned: Requirement = "Django==4.2.1".parse().unwrap();
+        assert_eq!(pinned.key(), "django");
+        assert!(pinned.spec.pinned().is_some());
+
+        let url: Requirement = "pkg @ https://example.com/pkg.whl".parse().unwrap();
+        assert!(url.spec.is_empty());
+        assert!("==1.0".parse::<Requirement>().is_err());
+        assert!("pkg >> 1".parse::<Requirement>().is_err());
+    }
+}
@@ -1,5 +1,7 @@
+use chrono::{DateTime, Duration, Local, Utc};
 use colored::Colorize;
 use std::io::{stdout, BufRead, Write};
+use std::path::Path;
 use tokio::fs;
 
 use crate::processes::exit_with_error;
@@ -33,6 +35,73 @@ pub fn confirm<R: std::io::Read>(input: R) -> bool {
     }
 }
 
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let meta = entry.path().symlink_metadata().ok()?;
+            if meta.is_dir() {
+                Some(dir_size(&entry.path()))
+            } else {
+                Some(meta.len())
+            }
+        })
+        .sum()
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+pub fn parse_age(age: &str) -> Result<Duration, String> {
+    let age = age.trim();
+    let split = age
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| format!("Missing unit in '{}', expected e.g. 12h, 90d or 8w", age))?;
+    let (value, unit) = age.split_at(split);
+    let value: i64 = value
+        .parse()
+        .map_err(|_| format!("Invalid number in '{}'", age))?;
+    let duration = match unit {
+        "h" => Duration::try_hours(value),
+        "d" => Duration::try_days(value),
+        "w" => Duration::try_weeks(value),
+        _ => {
+            return Err(format!(
+                "Invalid unit '{}' in '{}', expected h, d or w",
+                unit, age
+            ))
+        }
+    };
+    duration.ok_or_else(|| format!("Age '{}' is too large", age))
+}
+
+pub fn cutoff(age: Duration) -> Result<DateTime<Utc>, String> {
+    Utc::now()
+        .checked_sub_signed(age)
+        .ok_or_else(|| "Age is too large".to_string())
+}
+
+pub fn format_time(time: &DateTime<Utc>) -> String {
+    time.with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,6 +151,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("90d"), Ok(Duration::days(90)));
+        assert_eq!(parse_age("12h"), Ok(Duration::hours(12)));
+        assert_eq!(parse_age("2w"), Ok(Duration::weeks(2)));
+        assert!(parse_age("90").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("5y").is_err());
+        assert!(parse_age("99999999999999d").is_err());
+        assert!(parse_age("99999999999999999999h").is_err());
+        assert!(cutoff(parse_age("90d").unwrap()).is_ok());
+        assert!(cutoff(Duration::MAX).is_err());
+    }
+
+    #[test]
+    fn test_dir_size() {
+        let dir = std::env::temp_dir().join(format!("pypilot_dir_size_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("a"), [0u8; 100]).unwrap();
+        std::fs::write(dir.join("sub/b"), [0u8; 50]).unwrap();
+        let size = dir_size(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(size, 150);
+    }
+
     #[test]
     fn test_confirm_yes() {
         let cursor = std::io::Cursor::new("y\n");
@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+
+pub const MAX_NAME_LENGTH: usize = 64;
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Environment name cannot be empty".to_string());
+    }
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(format!(
+            "Environment name cannot be longer than {} characters",
+            MAX_NAME_LENGTH
+        ));
+    }
+    if name.starts_with('.') || name.starts_with('-') {
+        return Err(format!(
+            "Invalid environment name '{}': cannot start with '.' or '-'",
+            name
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(format!(
+            "Invalid environment name '{}': only letters, digits, '-', '_' and '.' are allowed",
+            name
+        ));
+    }
+    let stem = name.split('.').next().unwrap_or(name).to_ascii_uppercase();
+    if RESERVED_NAMES.contains(&stem.as_str()) {
+        return Err(format!(
+            "Invalid environment name '{}': the name is reserved",
+            name
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_tag(tag: &str) -> Result<(), String> {
+    if tag.is_empty()
+        || tag.len() > MAX_NAME_LENGTH
+        || !tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        return Err(format!(
+            "Invalid tag '{}': only letters, digits, '-', '_' and '.' are allowed",
+            tag
+        ));
+    }
+    Ok(())
+}
+
+pub fn ensure_inside(root: &Path, path: &Path) -> Result<PathBuf, String> {
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("Could not resolve {}: {}", root.display(), e))?;
+    let resolved = path
+        .canonicalize()
+        .map_err(|e| format!("Could not resolve {}: {}", path.display(), e))?;
+    if resolved != root && resolved.starts_with(&root) {
+        Ok(resolved)
+    } else {
+        Err(format!(
+            "Refusing to touch {}: it is not inside {}",
+            path.display(),
+            root.display()
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_validate_name_valid() {
+        assert!(validate_name("myenv").is_ok());
+        assert!(validate_name("ml-base_3.11").is_ok());
+    }
+
+    #[test]
+    fn test_validate_name_empty() {
+        assert!(validate_name("").is_err());
+    }
+
+    #[test]
+    fn test_validate_name_too_long() {
+        assert!(validate_name(&"a".repeat(MAX_NAME_LENGTH)).is_ok());
+        assert!(validate_name(&"a".repeat(MAX_NAME_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_validate_name_traversal() {
+        assert!(validate_name("..").is_err());
+        assert!(validate_name("../..").is_err());
+        assert!(validate_name("/etc").is_err());
+        assert!(validate_name("a/b").is_err());
+        assert!(validate_name("a\\b").is_err());
+        assert!(validate_name("C:").is_err());
+    }
+
+    #[test]
+    fn test_validate_name_hidden_or_flag() {
+        assert!(validate_name(".hidden").is_err());
+        assert!(validate_name("-rf").is_err());
+    }
+
+    #[test]
+    fn test_validate_name_invalid_chars() {
+        assert!(validate_name("my env").is_err());
+        assert!(validate_name("env*").is_err());
+        assert!(validate_name("envé").is_err());
+    }
+
+    #[test]
+    fn test_validate_name_reserved() {
+        assert!(validate_name("con").is_err());
+        assert!(validate_name("NUL.txt").is_err());
+        assert!(validate_name("console").is_ok());
+    }
+
+    #[test]
+    fn test_validate_tag() {
+        assert!(validate_tag("gpu").is_ok());
+        assert!(validate_tag("project-X_1.0").is_ok());
+        assert!(validate_tag("").is_err());
+        assert!(validate_tag("two words").is_err());
+        assert!(validate_tag("a,b").is_err());
+    }
+
+    #[test]
+    fn test_ensure_inside() {
+        let root = std::env::temp_dir().join(format!("pypilot_inside_{}", std::process::id()));
+        let child = root.join("child");
+        fs::create_dir_all(&child).unwrap();
+        assert!(ensure_inside(&root, &child).is_ok());
+        assert!(ensure_inside(&root, &root).is_err());
+        assert!(ensure_inside(&root, &root.join("child/../..")).is_err());
+        assert!(ensure_inside(&root, &root.join("missing")).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
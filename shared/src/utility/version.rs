@@ -0,0 +1,334 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PreRelease {
+    Alpha(u64),
+    Beta(u64),
+    Rc(u64),
+}
+
+#[derive(Debug, Clone)]
+pub struct Version {
+    pub release: Vec<u64>,
+    pub pre: Option<PreRelease>,
+    pub post: Option<u64>,
+    pub dev: Option<u64>,
+}
+
+impl Version {
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some() || self.dev.is_some()
+    }
+
+    fn part(&self, index: usize) -> u64 {
+        self.release.get(index).copied().unwrap_or(0)
+    }
+
+    fn phase(&self) -> (u8, Option<PreRelease>, u64, u8, u64) {
+        let stage = match (self.pre, self.post, self.dev) {
+            (None, None, Some(_)) => 0,
+            (Some(_), _, _) => 1,
+            (None, None, None) => 2,
+            (None, Some(_), _) => 3,
+        };
+        (
+            stage,
+            self.pre,
+            self.post.unwrap_or(0),
+            u8::from(self.dev.is_none()),
+            self.dev.unwrap_or(0),
+        )
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid version '{}'", s);
+        let lower = s.trim().to_lowercase();
+        let lower = lower.strip_prefix('v').unwrap_or(&lower);
+        let lower = lower.split('+').next().unwrap_or_default();
+
+        let release_end = lower
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(lower.len());
+        let release_str = lower[..release_end].trim_end_matches('.');
+        if release_str.is_empty() {
+            return Err(invalid());
+        }
+        let release = release_str
+            .split('.')
+            .map(|p| p.parse::<u64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut version = Version {
+            release,
+            pre: None,
+            post: None,
+            dev: None,
+        };
+        let mut rest = &lower[release_end..];
+        while !rest.is_empty() {
+            rest = rest.trim_start_matches(['.', '-', '_']);
+            let label_end = rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len());
+            let (label, after) = rest.split_at(label_end);
+            let after = after.trim_start_matches(['.', '-', '_']);
+            let number_end = after
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after.len());
+            let number = after[..number_end].parse::<u64>().unwrap_or(0);
+            rest = &after[number_end..];
+            match label {
+                "a" | "alpha" => version.pre = Some(PreRelease::Alpha(number)),
+                "b" | "beta" => version.pre = Some(PreRelease::Beta(number)),
+                "rc" | "c" | "pre" | "preview" => version.pre = Some(PreRelease::Rc(number)),
+                "post" | "rev" | "r" => version.post = Some(number),
+                "" if number_end > 0 => version.post = Some(number),
+                "dev" => version.dev = Some(number),
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(version)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let release: Vec<String> = self.release.iter().map(u64::to_string).collect();
+        write!(f, "{}", release.join("."))?;
+        match self.pre {
+            Some(PreRelease::Alpha(n)) => write!(f, "a{}", n)?,
+            Some(PreRelease::Beta(n)) => write!(f, "b{}", n)?,
+            Some(PreRelease::Rc(n)) => write!(f, "rc{}", n)?,
+            None => {}
+        }
+        if let Some(post) = self.post {
+            write!(f, ".post{}", post)?;
+        }
+        if let Some(dev) = self.dev {
+            write!(f, ".dev{}", dev)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.release.len().max(other.release.len());
+        (0..len)
+            .map(|i| self.part(i).cmp(&other.part(i)))
+            .find(|o| o.is_ne())
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.phase().cmp(&other.phase()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Equal,
+    NotEqual,
+    GreaterEqual,
+    LessEqual,
+    Greater,
+    Less,
+    Compatible,
+    Exact,
+}
+
+impl Operator {
+    const ALL: [(&'static str, Operator); 8] = [
+        ("===", Operator::Exact),
+        ("~=", Operator::Compatible),
+        ("==", Operator::Equal),
+        ("!=", Operator::NotEqual),
+        (">=", Operator::GreaterEqual),
+        ("<=", Operator::LessEqual),
+        (">", Operator::Greater),
+        ("<", Operator::Less),
+    ];
+
+    fn as_str(&self) -> &'static str {
+        Operator::ALL
+            .iter()
+            .find(|(_, op)| op == self)
+            .map(|(s, _)| *s)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Clause {
+    pub op: Operator,
+    pub version: Version,
+    pub wildcard: bool,
+}
+
+impl Clause {
+    fn prefix_matches(&self, version: &Version, len: usize) -> bool {
+        (0..len).all(|i| self.version.part(i) == version.part(i))
+    }
+
+    pub fn contains(&self, version: &Version) -> bool {
+        let len = self.version.release.len();
+        match self.op {
+            Operator::Equal | Operator::Exact if self.wildcard => self.prefix_matches(version, len),
+            Operator::NotEqual if self.wildcard => !self.prefix_matches(version, len),
+            Operator::Equal | Operator::Exact => version == &self.version,
+            Operator::NotEqual => version != &self.version,
+            Operator::GreaterEqual => version >= &self.version,
+            Operator::LessEqual => version <= &self.version,
+            Operator::Greater => version > &self.version,
+            Operator::Less => version < &self.version,
+            Operator::Compatible => {
+                version >= &self.version && self.prefix_matches(version, len.max(2) - 1)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Clause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.op.as_str(), self.version)?;
+        if self.wildcard {
+            write!(f, ".*")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VersionSpec {
+    pub clauses: Vec<Clause>,
+}
+
+impl VersionSpec {
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    pub fn contains(&self, version: &Version) -> bool {
+        self.clauses.iter().all(|c| c.contains(version))
+    }
+
+    pub fn pinned(&self) -> Option<&Version> {
+        match self.clauses.as_slice() {
+            [clause]
+                if matches!(clause.op, Operator::Equal | Operator::Exact) && !clause.wildcard =>
+            {
+                Some(&clause.version)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for VersionSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let clauses = s
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(|clause| {
+                let (op, rest) = Operator::ALL
+                    .iter()
+                    .find_map(|(prefix, op)| clause.strip_prefix(prefix).map(|r| (*op, r)))
+                    .ok_or_else(|| format!("Invalid version specifier '{}'", clause))?;
+                let rest = rest.trim();
+                let (rest, wildcard) = match rest.strip_suffix(".*") {
+                    Some(prefix) => (prefix, true),
+                    None => (rest, false),
+                };
+                Ok(Clause {
+                    op,
+                    version: rest.parse()?,
+                    wildcard,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(VersionSpec { clauses })
+    }
+}
+
+impl fmt::Display for VersionSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let clauses: Vec<String> = self.clauses.iter().map(Clause::to_string).collect();
+        write!(f, "{}", clauses.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let version = v("1.2.3rc1");
+        assert_eq!(version.release, vec![1, 2, 3]);
+        assert_eq!(version.pre, Some(PreRelease::Rc(1)));
+        assert_eq!(v("v2.0.post1").post, Some(1));
+        assert_eq!(v("1.0-1").post, Some(1));
+        assert_eq!(v("1.0.dev3").dev, Some(3));
+        assert_eq!(v("1.0+local.7").release, vec![1, 0]);
+        assert_eq!(v("0.4.18").to_string(), "0.4.18");
+        assert_eq!(v("V1.0-Beta.2_post3").to_string(), "1.0b2.post3");
+        assert!("abc".parse::<Version>().is_err());
+        assert!("1.0foo".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert_eq!(v("1.0"), v("1.0.0"));
+        assert!(v("0.4.18") < v("0.10.0"));
+        assert!(v("1.0.dev1") < v("1.0a1"));
+        assert!(v("1.0a1") < v("1.0b1"));
+        assert!(v("1.0b1") < v("1.0rc1"));
+        assert!(v("1.0rc1") < v("1.0"));
+        assert!(v("1.0") < v("1.0.post1"));
+        assert!(v("1.0.post1") < v("1.0.1"));
+        assert!(v("1.0a1.dev1") < v("1.0a1"));
+        assert!(v("1.0rc1").is_prerelease());
+    }
+
+    #[test]
+    fn test_version_spec() {
+        let spec: VersionSpec = ">=1.0, <2.0,!=1.5.0".parse().unwrap();
+        assert!(spec.contains(&v("1.4")));
+        assert!(!spec.contains(&v("1.5.0")));
+        assert!(!spec.contains(&v("2.0")));
+        assert_eq!(spec.to_string(), ">=1.0,<2.0,!=1.5.0");
+        let compatible: VersionSpec = "~=2.2.1".parse().unwrap();
+        assert!(compatible.contains(&v("2.2.9")));
+        assert!(!compatible.contains(&v("2.3.0")));
+        let wildcard: VersionSpec = "==1.2.*".parse().unwrap();
+        assert!(wildcard.contains(&v("1.2.7")));
+        assert!(!wildcard.contains(&v("1.3")));
+        assert_eq!(wildcard.pinned(), None);
+        let pinned: VersionSpec = "==2.31.0".parse().unwrap();
+        assert_eq!(pinned.pinned(), Some(&v("2.31.0")));
+        assert!("".parse::<VersionSpec>().unwrap().is_empty());
+        assert!("=>1.0".parse::<VersionSpec>().is_err());
+    }
+}
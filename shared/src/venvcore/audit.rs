@@ -0,0 +1,510 @@
+use super::{diff, venv::Venv};
+use crate::{constants::CURL_CMD, core::processes, requirement::normalize_name, version::Version};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+pub const DATABASE_FILE: &str = "PyPI.zip";
+const ECOSYSTEM: &str = "PyPI";
+
+#[derive(Debug, serde::Deserialize)]
+struct OsvRecord {
+    id: String,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    details: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    withdrawn: Option<String>,
+    #[serde(default)]
+    affected: Vec<OsvAffected>,
+    #[serde(default)]
+    database_specific: Option<OsvSeverity>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct OsvSeverity {
+    #[serde(default)]
+    severity: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct OsvAffected {
+    package: Option<OsvPackage>,
+    #[serde(default)]
+    ranges: Vec<OsvRange>,
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct OsvPackage {
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct OsvRange {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<OsvEvent>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct OsvEvent {
+    introduced: Option<String>,
+    fixed: Option<String>,
+    last_affected: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Introduced(Version),
+    Fixed(Version),
+    LastAffected(Version),
+}
+
+impl Event {
+    fn version(&self) -> &Version {
+        match self {
+            Event::Introduced(v) | Event::Fixed(v) | Event::LastAffected(v) => v,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Advisory {
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub summary: String,
+    pub severity: Option<String>,
+    pub ranges: Vec<Vec<Event>>,
+    pub versions: Vec<Version>,
+}
+
+impl Advisory {
+    pub fn affects(&self, version: &Version) -> bool {
+        self.versions.contains(version)
+            || self.ranges.iter().any(|events| in_range(events, version))
+    }
+
+    pub fn fixed_in(&self, version: &Version) -> Option<Version> {
+        self.ranges
+            .iter()
+            .filter(|events| in_range(events, version))
+            .flatten()
+            .filter_map(|event| match event {
+                Event::Fixed(v) if v > version => Some(v.clone()),
+                _ => None,
+            })
+            .min()
+    }
+}
+
+fn in_range(events: &[Event], version: &Version) -> bool {
+    let mut affected = false;
+    for event in events {
+        match event {
+            Event::Introduced(v) if v <= version => affected = true,
+            Event::Fixed(v) if v <= version => affected = false,
+            Event::LastAffected(v) if v < version => affected = false,
+            _ => {}
+        }
+    }
+    affected
+}
+
+fn earliest() -> Version {
+    Version {
+        release: vec![0],
+        pre: None,
+        post: None,
+        dev: Some(0),
+    }
+}
+
+fn parse_events(events: &[OsvEvent], skipped: &mut usize) -> Vec<Event> {
+    let mut parsed: Vec<Event> = events
+        .iter()
+        .filter_map(|e| {
+            let event = if let Some(v) = &e.introduced {
+                Some(Event::Introduced(v.parse().unwrap_or_else(|_| earliest())))
+            } else if let Some(v) = &e.fixed {
+                v.parse().ok().map(Event::Fixed)
+            } else if let Some(v) = &e.last_affected {
+                v.parse().ok().map(Event::LastAffected)
+            } else {
+                None
+            };
+            if event.is_none() {
+                *skipped += 1;
+            }
+            event
+        })
+        .collect();
+    parsed.sort_by(|a, b| a.version().cmp(b.version()));
+    parsed
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AdvisoryDb {
+    packages: BTreeMap<String, Vec<Advisory>>,
+    pub skipped_records: usize,
+    pub skipped_events: usize,
+}
+
+impl AdvisoryDb {
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        let mut ids: Vec<&str> = self
+            .packages
+            .values()
+            .flatten()
+            .map(|a| a.id.as_str())
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.len()
+    }
+
+    pub fn advisories(&self, name: &str) -> &[Advisory] {
+        self.packages
+            .get(&normalize_name(name))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn add_json(&mut self, data: &[u8]) -> Result<(), String> {
+        let record: OsvRecord = serde_json::from_slice(data).map_err(|e| e.to_string())?;
+        if record.withdrawn.is_some() {
+            return Ok(());
+        }
+        let summary = record
+            .summary
+            .or_else(|| {
+                record
+                    .details
+                    .and_then(|d| d.lines().next().map(str::to_string))
+            })
+            .unwrap_or_default();
+        let severity = record.database_specific.and_then(|d| d.severity);
+        for affected in record.affected {
+            let Some(package) = affected.package else {
+                continue;
+            };
+            if package.ecosystem != ECOSYSTEM {
+                continue;
+            }
+            let advisory = Advisory {
+                id: record.id.clone(),
+                aliases: record.aliases.clone(),
+                summary: summary.clone(),
+                severity: severity.clone(),
+                ranges: affected
+                    .ranges
+                    .iter()
+                    .filter(|r| r.kind == "ECOSYSTEM")
+                    .map(|r| parse_events(&r.events, &mut self.skipped_events))
+                    .collect(),
+                versions: affected
+                    .versions
+                    .iter()
+                    .filter_map(|v| {
+                        let version = v.parse().ok();
+                        if version.is_none() {
+                            self.skipped_events += 1;
+                        }
+                        version
+                    })
+                    .collect(),
+            };
+            self.packages
+                .entry(normalize_name(&package.name))
+                .or_default()
+                .push(advisory);
+        }
+        Ok(())
+    }
+
+    fn add_zip(&mut self, path: &Path) -> Result<(), String> {
+        let file = fs::File::open(path).map_err(|e| e.to_string())?;
+        let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+            if !entry.is_file() || !entry.name().ends_with(".json") {
+                continue;
+            }
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).map_err(|e| e.to_string())?;
+            if self.add_json(&data).is_err() {
+                self.skipped_records += 1;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let mut db = AdvisoryDb::default();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(current) = pending.pop() {
+            let Ok(entries) = fs::read_dir(&current) else {
+                continue;
+            };
+            for path in entries.filter_map(Result::ok).map(|e| e.path()) {
+                let extension = path.extension().and_then(|e| e.to_str());
+                if path.is_dir() {
+                    pending.push(path);
+                } else if extension == Some("zip") {
+                    db.add_zip(&path)
+                        .map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+                } else if extension == Some("json") {
+                    let data = fs::read(&path).map_err(|e| e.to_string())?;
+                    if db.add_json(&data).is_err() {
+                        db.skipped_records += 1;
+                    }
+                }
+            }
+        }
+        if db.is_empty() {
+            return Err(format!(
+                "No advisories found in {}, run 'pypilot audit --sync' first",
+                dir.display()
+            ));
+        }
+        Ok(db)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub package: String,
+    pub installed: Version,
+    pub id: String,
+    pub aliases: Vec<String>,
+    pub summary: String,
+    pub severity: Option<String>,
+    pub fixed: Option<Version>,
+}
+
+pub fn audit(db: &AdvisoryDb, installed: &BTreeMap<String, (String, String)>) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = installed
+        .values()
+        .filter_map(|(name, version)| Some((name, version.parse::<Version>().ok()?)))
+        .flat_map(|(name, version)| {
+            db.advisories(name)
+                .iter()
+                .filter(|a| a.affects(&version))
+                .map(|a| Finding {
+                    package: name.clone(),
+                    installed: version.clone(),
+                    id: a.id.clone(),
+                    aliases: a.aliases.clone(),
+                    summary: a.summary.clone(),
+                    severity: a.severity.clone(),
+                    fixed: a.fixed_in(&version),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    findings.sort_by(|a, b| {
+        normalize_name(&a.package)
+            .cmp(&normalize_name(&b.package))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    findings.dedup_by(|a, b| a.package == b.package && a.id == b.id);
+    findings
+}
+
+pub fn check(venv: &Venv, db: &AdvisoryDb) -> Vec<Finding> {
+    audit(db, &diff::installed(venv))
+}
+
+pub async fn sync(dir: &Path, source: &str) -> Result<AdvisoryDb, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Error creating {}: {}", dir.display(), e))?;
+    let target = dir.join(DATABASE_FILE);
+    let partial = PathBuf::from(format!("{}.part", target.display()));
+    let local = Path::new(source.strip_prefix("file://").unwrap_or(source));
+    let fetched = if local.is_file() {
+        fs::copy(local, &partial)
+            .map(|_| ())
+            .map_err(|e| format!("Error copying {}: {}", local.display(), e))
+    } else if which::which(CURL_CMD).is_err() {
+        Err(format!(
+            "{} is required to download {}, install it or set advisory_url to a local .zip file",
+            CURL_CMD, source
+        ))
+    } else {
+        let output = partial.display().to_string();
+        processes::capture_output(CURL_CMD, &["-LsSf", "-o", output.as_str(), source])
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Error downloading {}: {}", source, e))
+    };
+    let mut db = AdvisoryDb::default();
+    let result = fetched.and_then(|_| {
+        db.add_zip(&partial)
+            .map_err(|e| format!("Invalid advisory database from {}: {}", source, e))
+    });
+    if let Err(e) = result {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, &target)
+        .map_err(|e| format!("Error writing {}: {}", target.display(), e))?;
+    Ok(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn v(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    const REQUESTS: &str = r#"{
+        "id": "GHSA-9wx4-h78v-vm56",
+        "summary": "Requests leaks proxy credentials",
+        "aliases": ["CVE-2024-35195"],
+        "affected": [{
+            "package": {"ecosystem": "PyPI", "name": "Requests"},
+            "ranges": [
+                {"type": "ECOSYSTEM", "events": [{"introduced": "0"}, {"fixed": "2.32.0"}]},
+                {"type": "GIT", "events": [{"introduced": "abc"}]}
+            ]
+        }],
+        "database_specific": {"severity": "MODERATE"}
+    }"#;
+
+    const JINJA: &str = r#"{
+        "id": "PYSEC-2024-1",
+        "details": "Sandbox escape\nMore details",
+        "affected": [{
+            "package": {"ecosystem": "PyPI", "name": "jinja2"},
+            "ranges": [{"type": "ECOSYSTEM", "events": [
+                {"introduced": "3.0.0"}, {"fixed": "3.1.3"},
+                {"introduced": "2.0"}, {"fixed": "2.11.3"}
+            ]}],
+            "versions": ["1.2"]
+        }]
+    }"#;
+
+    fn db() -> AdvisoryDb {
+        let mut db = AdvisoryDb::default();
+        db.add_json(REQUESTS.as_bytes()).unwrap();
+        db.add_json(JINJA.as_bytes()).unwrap();
+        db.add_json(br#"{"id": "X", "withdrawn": "2024-01-01T00:00:00Z", "affected": [{"package": {"ecosystem": "PyPI", "name": "six"}, "ranges": [{"type": "ECOSYSTEM", "events": [{"introduced": "0"}]}]}]}"#).unwrap();
+        db.add_json(br#"{"id": "Y", "affected": [{"package": {"ecosystem": "npm", "name": "six"}, "ranges": [{"type": "ECOSYSTEM", "events": [{"introduced": "0"}]}]}]}"#).unwrap();
+        db
+    }
+
+    #[test]
+    fn test_affects() {
+        let db = db();
+        let requests = &db.advisories("requests")[0];
+        assert!(requests.affects(&v("2.31.0")));
+        assert!(!requests.affects(&v("2.32.0")));
+        assert_eq!(requests.fixed_in(&v("2.31.0")), Some(v("2.32.0")));
+
+        let jinja = &db.advisories("Jinja2")[0];
+        assert!(jinja.affects(&v("2.10")));
+        assert_eq!(jinja.fixed_in(&v("2.10")), Some(v("2.11.3")));
+        assert!(!jinja.affects(&v("2.11.3")));
+        assert!(jinja.affects(&v("3.1.2")));
+        assert_eq!(jinja.fixed_in(&v("3.1.2")), Some(v("3.1.3")));
+        assert!(jinja.affects(&v("1.2")));
+        assert_eq!(jinja.fixed_in(&v("1.2")), None);
+        assert_eq!(jinja.summary, "Sandbox escape");
+
+        assert!(db.advisories("six").is_empty());
+        assert_eq!(db.len(), 2);
+    }
+
+    #[test]
+    fn test_last_affected() {
+        let mut db = AdvisoryDb::default();
+        db.add_json(br#"{"id": "Z", "affected": [{"package": {"ecosystem": "PyPI", "name": "pkg"}, "ranges": [{"type": "ECOSYSTEM", "events": [{"introduced": "1.0"}, {"last_affected": "1.4"}]}]}]}"#).unwrap();
+        let advisory = &db.advisories("pkg")[0];
+        assert!(advisory.affects(&v("1.4")));
+        assert!(!advisory.affects(&v("1.4.1")));
+        assert!(!advisory.affects(&v("0.9")));
+    }
+
+    #[test]
+    fn test_skipped() {
+        let mut db = AdvisoryDb::default();
+        db.add_json(br#"{"id": "W", "affected": [{"package": {"ecosystem": "PyPI", "name": "pkg"}, "ranges": [{"type": "ECOSYSTEM", "events": [{"introduced": "not-a-version"}, {"fixed": "also bad"}]}], "versions": ["???", "1.0"]}]}"#).unwrap();
+        let advisory = &db.advisories("pkg")[0];
+        assert!(advisory.affects(&v("0.0.1")));
+        assert!(advisory.affects(&v("99.0")));
+        assert_eq!(db.skipped_events, 2);
+        assert!(db.add_json(b"{broken").is_err());
+    }
+
+    #[test]
+    fn test_audit() {
+        let installed: BTreeMap<String, (String, String)> = [
+            ("requests", "2.31.0"),
+            ("jinja2", "3.1.4"),
+            ("six", "1.16.0"),
+        ]
+        .iter()
+        .map(|(n, v)| (n.to_string(), (n.to_string(), v.to_string())))
+        .collect();
+        let findings = audit(&db(), &installed);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].package, "requests");
+        assert_eq!(findings[0].id, "GHSA-9wx4-h78v-vm56");
+        assert_eq!(findings[0].aliases, vec!["CVE-2024-35195"]);
+        assert_eq!(findings[0].severity.as_deref(), Some("MODERATE"));
+        assert_eq!(findings[0].fixed, Some(v("2.32.0")));
+    }
+
+    #[tokio::test]
+    async fn test_sync_and_load() {
+        let root = std::env::temp_dir().join(format!("pypilot_audit_{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let source = root.join("all.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&source).unwrap());
+        for (name, data) in [("GHSA-1.json", REQUESTS), ("PYSEC-1.json", JINJA)] {
+            writer
+                .start_file(name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let db_dir = root.join("db");
+        let empty = AdvisoryDb::load(&db_dir);
+        let synced = sync(&db_dir, &source.display().to_string()).await;
+        fs::write(
+            db_dir.join("local.json"),
+            REQUESTS.replace("GHSA-9wx4-h78v-vm56", "LOCAL-1"),
+        )
+        .unwrap();
+        fs::write(db_dir.join("broken.json"), "{broken").unwrap();
+        let loaded = AdvisoryDb::load(&db_dir);
+        let invalid = sync(&db_dir, &db_dir.join("local.json").display().to_string()).await;
+        let kept = db_dir.join(DATABASE_FILE).is_file();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(empty.is_err());
+        assert_eq!(synced.map(|db| db.len()), Ok(2));
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.len(), 3);
+        assert_eq!(loaded.advisories("requests").len(), 2);
+        assert_eq!(loaded.skipped_records, 1);
+        assert!(invalid.is_err());
+        assert!(kept);
+    }
+}
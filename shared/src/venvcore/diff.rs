@@ -0,0 +1,280 @@
+use super::venv::Venv;
+use crate::{
+    requirement::{normalize_name, Requirement},
+    version::Version,
+};
+use std::{collections::BTreeMap, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    OnlyLeft,
+    OnlyRight,
+    Changed,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DiffEntry {
+    pub name: String,
+    pub left: Option<String>,
+    pub right: Option<String>,
+    pub change: Change,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PackageDiff {
+    pub left: String,
+    pub right: String,
+    pub entries: Vec<DiffEntry>,
+}
+
+impl PackageDiff {
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffFormat {
+    Table,
+    Json,
+}
+
+impl FromStr for DiffFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "table" => Ok(DiffFormat::Table),
+            "json" => Ok(DiffFormat::Json),
+            other => Err(format!("Unknown format '{}', use table or json", other)),
+        }
+    }
+}
+
+pub fn installed(venv: &Venv) -> BTreeMap<String, (String, String)> {
+    venv.distributions()
+        .into_iter()
+        .map(|d| (d.key(), (d.name, d.version)))
+        .collect()
+}
+
+fn same_version(a: &str, b: &str) -> bool {
+    match (a.parse::<Version>(), b.parse::<Version>()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+pub fn compare(
+    left: &BTreeMap<String, (String, String)>,
+    right: &BTreeMap<String, (String, String)>,
+) -> Vec<DiffEntry> {
+    let mut entries = Vec::new();
+    for (key, (name, version)) in left {
+        match right.get(key) {
+            None => entries.push(DiffEntry {
+                name: name.clone(),
+                left: Some(version.clone()),
+                right: None,
+                change: Change::OnlyLeft,
+            }),
+            Some((_, other)) if !same_version(version, other) => entries.push(DiffEntry {
+                name: name.clone(),
+                left: Some(version.clone()),
+                right: Some(other.clone()),
+                change: Change::Changed,
+            }),
+            Some(_) => {}
+        }
+    }
+    for (key, (name, version)) in right {
+        if !left.contains_key(key) {
+            entries.push(DiffEntry {
+                name: name.clone(),
+                left: None,
+                right: Some(version.clone()),
+                change: Change::OnlyRight,
+            });
+        }
+    }
+    entries.sort_by_key(|e| normalize_name(&e.name));
+    entries
+}
+
+pub fn compare_venvs(left: &Venv, right: &Venv) -> PackageDiff {
+    PackageDiff {
+        left: left.name.clone(),
+        right: right.name.clone(),
+        entries: compare(&installed(left), &installed(right)),
+    }
+}
+
+pub fn parse_requirements(lines: &[String]) -> Result<Vec<Requirement>, String> {
+    lines
+        .iter()
+        .map(|line| line.split(" #").next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('-'))
+        .map(str::parse)
+        .collect()
+}
+
+pub fn compare_requirements(venv: &Venv, label: &str, requirements: &[Requirement]) -> PackageDiff {
+    let left = installed(venv);
+    let env = venv.marker_env();
+    let requirements: Vec<&Requirement> = requirements
+        .iter()
+        .filter(|r| {
+            r.marker
+                .as_deref()
+                .is_none_or(|m| env.evaluate(m) != Some(false))
+        })
+        .collect();
+    let mut entries: Vec<DiffEntry> = left
+        .iter()
+        .filter(|(key, _)| !requirements.iter().any(|r| &r.key() == *key))
+        .map(|(_, (name, version))| DiffEntry {
+            name: name.clone(),
+            left: Some(version.clone()),
+            right: None,
+            change: Change::OnlyLeft,
+        })
+        .collect();
+    for requirement in &requirements {
+        let wanted = if requirement.spec.is_empty() {
+            "any".to_string()
+        } else {
+            requirement.spec.to_string()
+        };
+        match left.get(&requirement.key()) {
+            None => entries.push(DiffEntry {
+                name: requirement.name.clone(),
+                left: None,
+                right: Some(wanted),
+                change: Change::OnlyRight,
+            }),
+            Some((name, version)) => {
+                let satisfied = version
+                    .parse::<Version>()
+                    .map(|v| requirement.spec.contains(&v))
+                    .unwrap_or(requirement.spec.is_empty());
+                if !satisfied {
+                    entries.push(DiffEntry {
+                        name: name.clone(),
+                        left: Some(version.clone()),
+                        right: Some(wanted),
+                        change: Change::Changed,
+                    });
+                }
+            }
+        }
+    }
+    entries.sort_by_key(|e| normalize_name(&e.name));
+    PackageDiff {
+        left: venv.name.clone(),
+        right: label.to_string(),
+        entries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::venvcore::testutil;
+    use std::fs;
+
+    fn fake_venv(name: &str, packages: &[&str]) -> Venv {
+        let venv = testutil::fake_venv("diff", name);
+        testutil::fake_dist_infos(&venv, packages);
+        venv
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("JSON".parse::<DiffFormat>(), Ok(DiffFormat::Json));
+        assert_eq!("table".parse::<DiffFormat>(), Ok(DiffFormat::Table));
+        assert!("csv".parse::<DiffFormat>().is_err());
+    }
+
+    #[test]
+    fn test_compare_venvs() {
+        let a = fake_venv("a", &["requests-2.31.0", "six-1.16.0", "numpy-1.26.0"]);
+        let b = fake_venv("b", &["requests-2.32.0", "six-1.16", "pandas-2.1.0"]);
+        let diff = compare_venvs(&a, &b);
+        fs::remove_dir_all(&a.path).unwrap();
+        fs::remove_dir_all(&b.path).unwrap();
+        assert_eq!(diff.left, "a");
+        assert_eq!(
+            diff.entries
+                .iter()
+                .map(|e| (e.name.as_str(), e.change))
+                .collect::<Vec<_>>(),
+            vec![
+                ("numpy", Change::OnlyLeft),
+                ("pandas", Change::OnlyRight),
+                ("requests", Change::Changed),
+            ]
+        );
+        assert_eq!(diff.entries[2].right.as_deref(), Some("2.32.0"));
+    }
+
+    #[test]
+    fn test_compare_requirements() {
+        let venv = fake_venv(
+            "req",
+            &["requests-2.31.0", "six-1.16.0", "typing_extensions-4.8.0"],
+        );
+        let lines = vec![
+            "requests>=2.32  # security fix".to_string(),
+            "Typing-Extensions".to_string(),
+            "pandas==2.1.0".to_string(),
+            "--index-url https://pypi.example.com/simple".to_string(),
+            "pywin32==306; sys_platform == 'nonexistent'".to_string(),
+            "numpy==1.26.0; python_version < '3.0'".to_string(),
+        ];
+        fs::write(venv.path.join("pyvenv.cfg"), "version_info = 3.11.4\n").unwrap();
+        let requirements = parse_requirements(&lines).unwrap();
+        let diff = compare_requirements(&venv, "requirements.txt", &requirements);
+        fs::remove_dir_all(&venv.path).unwrap();
+        assert_eq!(
+            diff.entries,
+            vec![
+                DiffEntry {
+                    name: "pandas".to_string(),
+                    left: None,
+                    right: Some("==2.1.0".to_string()),
+                    change: Change::OnlyRight,
+                },
+                DiffEntry {
+                    name: "requests".to_string(),
+                    left: Some("2.31.0".to_string()),
+                    right: Some(">=2.32".to_string()),
+                    change: Change::Changed,
+                },
+                DiffEntry {
+                    name: "six".to_string(),
+                    left: Some("1.16.0".to_string()),
+                    right: None,
+                    change: Change::OnlyLeft,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_json() {
+        let diff = PackageDiff {
+            left: "a".to_string(),
+            right: "b".to_string(),
+            entries: vec![DiffEntry {
+                name: "six".to_string(),
+                left: Some("1.16.0".to_string()),
+                right: None,
+                change: Change::OnlyLeft,
+            }],
+        };
+        let json = diff.to_json().unwrap();
+        assert!(json.contains(r#""change": "only_left""#));
+        assert!(json.contains(r#""right": null"#));
+    }
+}
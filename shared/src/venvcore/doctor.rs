@@ -0,0 +1,367 @@
+use super::{metadata::Metadata, venv::Venv};
+use crate::{
+    constants::{DIST_INFO_METADATA, DOCTOR_BACKUP_DIR},
+    validation,
+    version::Version,
+};
+use colored::Colorize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    MissingConfig,
+    MissingHome(PathBuf),
+    BrokenSymlink(PathBuf),
+    InterpreterFails(String),
+    InconsistentMetadata(String),
+    UvMissing,
+}
+
+impl Issue {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Issue::InconsistentMetadata(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    pub fn fixable(&self) -> bool {
+        !matches!(self, Issue::UvMissing | Issue::MissingConfig)
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::MissingConfig => write!(f, "pyvenv.cfg is missing"),
+            Issue::MissingHome(home) => {
+                write!(f, "base interpreter folder {} is missing", home.display())
+            }
+            Issue::BrokenSymlink(link) => write!(f, "broken symlink {}", link.display()),
+            Issue::InterpreterFails(e) => write!(f, "interpreter does not run: {}", e),
+            Issue::InconsistentMetadata(e) => write!(f, "inconsistent package metadata: {}", e),
+            Issue::UvMissing => write!(f, "Astral UV is not installed"),
+        }
+    }
+}
+
+pub async fn check(venv: &Venv) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    match venv.config() {
+        None => issues.push(Issue::MissingConfig),
+        Some(cfg) => {
+            if let Some(home) = cfg.get("home").map(PathBuf::from) {
+                if !home.exists() {
+                    issues.push(Issue::MissingHome(home));
+                }
+            }
+        }
+    }
+    issues.extend(
+        broken_symlinks(&venv.bin_dir())
+            .into_iter()
+            .map(Issue::BrokenSymlink),
+    );
+    if let Err(e) = venv.verify_interpreter().await {
+        issues.push(Issue::InterpreterFails(e));
+    }
+    issues.extend(
+        metadata_problems(venv)
+            .into_iter()
+            .map(Issue::InconsistentMetadata),
+    );
+    issues
+}
+
+pub fn recorded_packages(venv: &Venv) -> Vec<String> {
+    let mut latest = BTreeMap::new();
+    for dist in venv.distributions() {
+        let version = dist.version.parse::<Version>().ok();
+        match latest.get(&dist.key()) {
+            Some((current, _)) if *current >= version => {}
+            _ => {
+                latest.insert(dist.key(), (version, dist));
+            }
+        }
+    }
+    latest
+        .into_values()
+        .map(|(_, d)| format!("{}=={}", d.name, d.version))
+        .collect()
+}
+
+pub async fn fix(venv: &Venv) -> Result<(), String> {
+    let python_version = venv
+        .config()
+        .and_then(|cfg| cfg.get("version_info").or(cfg.get("version")).cloned())
+        .ok_or("Cannot determine the python version of the environment")?;
+    validation::ensure_inside(&venv.settings.venvs_path, &venv.path)?;
+    let packages = recorded_packages(venv);
+    let metadata = Metadata::load(&venv.path);
+    let backup_dir = venv.settings.state_dir().join(DOCTOR_BACKUP_DIR);
+    fs::create_dir_all(&backup_dir).map_err(|e| format!("Error creating backup: {}", e))?;
+    let backup = backup_dir.join(&venv.name);
+    fs::rename(&venv.path, &backup).map_err(|e| format!("Error creating backup: {}", e))?;
+    let mut recreated = Venv::new(venv.name.clone(), python_version, packages, false);
+    recreated.path = venv.path.clone();
+    recreated.tags = venv.tags.clone();
+    recreated.description = venv.description.clone();
+    recreated.index_profile = venv.index_profile.clone();
+    recreated.index_url = venv.index_url.clone();
+    recreated.wheelhouse = venv.wheelhouse.clone();
+    recreated.offline = venv.offline;
+    match recreated.create().await {
+        Ok(_) => {
+            let _ = fs::remove_dir_all(&backup);
+            metadata.save(&venv.path)
+        }
+        Err(e) => {
+            let _ = fs::remove_dir_all(&venv.path);
+            fs::rename(&backup, &venv.path)
+                .map_err(|e| format!("Error restoring backup: {}", e))?;
+            Err(e)
+        }
+    }
+}
+
+pub fn print_issues(name: &str, issues: &[Issue]) {
+    if issues.is_empty() {
+        println!("{} {}", name.cyan(), "OK".green());
+        return;
+    }
+    println!("{}", name.cyan());
+    for issue in issues {
+        match issue.severity() {
+            Severity::Error => println!("  {} {}", "error:".red(), issue),
+            Severity::Warning => println!("  {} {}", "warning:".yellow(), issue),
+        }
+    }
+}
+
+fn broken_symlinks(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut broken: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| {
+            p.symlink_metadata()
+                .map(|m| m.file_type().is_symlink())
+                .unwrap_or(false)
+                && fs::metadata(p).is_err()
+        })
+        .collect();
+    broken.sort();
+    broken
+}
+
+fn parse_dist_info_name(dir: &Path) -> Option<(String, String)> {
+    let stem = dir.file_stem()?.to_str()?;
+    let (name, version) = stem.split_once('-')?;
+    Some((name.to_string(), version.to_string()))
+}
+
+fn metadata_problems(venv: &Venv) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut seen: HashMap<String, Vec<String>> = HashMap::new();
+    for dir in venv.dist_info_dirs() {
+        let label = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if !dir.join(DIST_INFO_METADATA).is_file() {
+            problems.push(format!("{} has no METADATA", label));
+        }
+        if let Some((name, version)) = parse_dist_info_name(&dir) {
+            seen.entry(name.to_lowercase().replace(['-', '.'], "_"))
+                .or_default()
+                .push(version);
+        }
+    }
+    let mut duplicates: Vec<String> = seen
+        .into_iter()
+        .filter(|(_, versions)| versions.len() > 1)
+        .map(|(name, mut versions)| {
+            versions.sort();
+            format!(
+                "{} is installed more than once ({})",
+                name,
+                versions.join(", ")
+            )
+        })
+        .collect();
+    duplicates.sort();
+    problems.extend(duplicates);
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::venvcore::testutil::{self, site_packages};
+
+    fn fake_venv(name: &str) -> Venv {
+        let venv = testutil::fake_venv("doctor", name);
+        fs::create_dir_all(venv.bin_dir()).unwrap();
+        venv
+    }
+
+    #[test]
+    fn test_severity() {
+        assert_eq!(Issue::MissingConfig.severity(), Severity::Error);
+        assert_eq!(
+            Issue::InconsistentMetadata("x".to_string()).severity(),
+            Severity::Warning
+        );
+        assert!(!Issue::UvMissing.fixable());
+        assert!(Issue::MissingHome(PathBuf::from("/x")).fixable());
+    }
+
+    #[tokio::test]
+    async fn test_check_missing_config() {
+        let venv = fake_venv("missing_config");
+        let issues = check(&venv).await;
+        fs::remove_dir_all(&venv.path).unwrap();
+        assert!(issues.contains(&Issue::MissingConfig));
+        assert!(issues
+            .iter()
+            .any(|i| matches!(i, Issue::InterpreterFails(_))));
+    }
+
+    #[tokio::test]
+    async fn test_check_missing_home() {
+        let venv = fake_venv("missing_home");
+        fs::write(
+            venv.path.join("pyvenv.cfg"),
+            "home = /pypilot/removed/python/bin\nversion_info = 3.11.4\n",
+        )
+        .unwrap();
+        let issues = check(&venv).await;
+        fs::remove_dir_all(&venv.path).unwrap();
+        assert!(issues.contains(&Issue::MissingHome(PathBuf::from(
+            "/pypilot/removed/python/bin"
+        ))));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_check_broken_symlink() {
+        let venv = fake_venv("broken_symlink");
+        let link = venv.bin_dir().join("python");
+        std::os::unix::fs::symlink("/pypilot/removed/python3", &link).unwrap();
+        let issues = check(&venv).await;
+        fs::remove_dir_all(&venv.path).unwrap();
+        assert!(issues.contains(&Issue::BrokenSymlink(link)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_check_healthy() {
+        let Ok(python) = which::which("python3") else {
+            println!("Skipping test, python3 not found");
+            return;
+        };
+        let venv = fake_venv("healthy");
+        let home = python.parent().unwrap().to_path_buf();
+        std::os::unix::fs::symlink(&python, venv.bin_dir().join("python")).unwrap();
+        fs::write(
+            venv.path.join("pyvenv.cfg"),
+            format!("home = {}\nversion_info = 3.11.4\n", home.display()),
+        )
+        .unwrap();
+        let issues = check(&venv).await;
+        fs::remove_dir_all(&venv.path).unwrap();
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_metadata_problems() {
+        let venv = fake_venv("metadata");
+        let sp = site_packages(&venv);
+        fs::create_dir_all(sp.join("requests-2.31.0.dist-info")).unwrap();
+        fs::write(
+            sp.join("requests-2.31.0.dist-info")
+                .join(DIST_INFO_METADATA),
+            "",
+        )
+        .unwrap();
+        fs::create_dir_all(sp.join("Requests-2.30.0.dist-info")).unwrap();
+        fs::write(
+            sp.join("Requests-2.30.0.dist-info")
+                .join(DIST_INFO_METADATA),
+            "",
+        )
+        .unwrap();
+        fs::create_dir_all(sp.join("numpy-1.26.0.dist-info")).unwrap();
+        let problems = metadata_problems(&venv);
+        fs::remove_dir_all(&venv.path).unwrap();
+        assert_eq!(
+            problems,
+            vec![
+                "numpy-1.26.0.dist-info has no METADATA".to_string(),
+                "requests is installed more than once (2.30.0, 2.31.0)".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recorded_packages() {
+        let venv = fake_venv("recorded");
+        let sp = site_packages(&venv);
+        fs::create_dir_all(sp.join("requests-2.31.0.dist-info")).unwrap();
+        fs::create_dir_all(sp.join("numpy-1.26.0.dist-info")).unwrap();
+        fs::create_dir_all(sp.join("numpy")).unwrap();
+        fs::create_dir_all(sp.join("Requests-2.30.0.dist-info")).unwrap();
+        fs::create_dir_all(sp.join("zope_interface-6.0.dist-info")).unwrap();
+        fs::write(
+            sp.join("zope_interface-6.0.dist-info")
+                .join(DIST_INFO_METADATA),
+            "Name: zope.interface\nVersion: 6.0\n",
+        )
+        .unwrap();
+        let pkgs = recorded_packages(&venv);
+        fs::remove_dir_all(&venv.path).unwrap();
+        assert_eq!(
+            pkgs,
+            vec!["numpy==1.26.0", "requests==2.31.0", "zope.interface==6.0"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fix_restores_backup_on_failure() {
+        let mut venv = fake_venv("restore");
+        fs::write(venv.path.join("pyvenv.cfg"), "version_info = 3.11.4\n").unwrap();
+        let metadata = Metadata {
+            created: chrono::DateTime::from_timestamp(1_700_000_000, 0),
+            ..Metadata::default()
+        };
+        metadata.save(&venv.path).unwrap();
+        venv.wheelhouse = Some(PathBuf::from("/pypilot/removed/wheels"));
+        let result = fix(&venv).await;
+        let restored = Metadata::load(&venv.path);
+        let leftovers: Vec<_> = fs::read_dir(&venv.settings.venvs_path)
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_name().to_string_lossy().contains("restore"))
+            .map(|e| e.file_name())
+            .collect();
+        let backup = venv.settings.state_dir().join(DOCTOR_BACKUP_DIR);
+        let backups = fs::read_dir(&backup).unwrap().count();
+        fs::remove_dir_all(&venv.path).unwrap();
+        assert!(result.is_err());
+        assert_eq!(restored, metadata);
+        assert_eq!(leftovers, vec!["restore"]);
+        assert_eq!(backups, 0);
+    }
+}
@@ -0,0 +1,352 @@
+use super::{
+    doctor::{self, Severity},
+    metadata::Metadata,
+    venv::Venv,
+};
+use crate::utils;
+use chrono::{DateTime, Utc};
+use std::{fs, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Size,
+    Packages,
+    Created,
+    Used,
+    Path,
+    Status,
+    Tags,
+    Description,
+}
+
+impl Column {
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Size => "Size",
+            Column::Packages => "Packages",
+            Column::Created => "Created",
+            Column::Used => "Last used",
+            Column::Path => "Path",
+            Column::Status => "Status",
+            Column::Tags => "Tags",
+            Column::Description => "Description",
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "size" => Ok(Column::Size),
+            "packages" | "pkgs" => Ok(Column::Packages),
+            "created" => Ok(Column::Created),
+            "used" | "last-used" => Ok(Column::Used),
+            "path" => Ok(Column::Path),
+            "status" => Ok(Column::Status),
+            "tags" => Ok(Column::Tags),
+            "description" | "desc" => Ok(Column::Description),
+            other => Err(format!(
+                "unknown column '{}', expected one of: size, packages, created, used, path, status, tags, description",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey {
+    #[default]
+    Name,
+    Size,
+    Created,
+    Used,
+}
+
+impl SortKey {
+    fn column(&self) -> Option<Column> {
+        match self {
+            SortKey::Name => None,
+            SortKey::Size => Some(Column::Size),
+            SortKey::Created => Some(Column::Created),
+            SortKey::Used => Some(Column::Used),
+        }
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "name" => Ok(SortKey::Name),
+            "size" => Ok(SortKey::Size),
+            "created" => Ok(SortKey::Created),
+            "used" => Ok(SortKey::Used),
+            other => Err(format!(
+                "unknown sort key '{}', expected one of: name, size, created, used",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    Python(String),
+    Status(String),
+    Tag(String),
+}
+
+impl Filter {
+    fn column(&self) -> Option<Column> {
+        match self {
+            Filter::Python(_) | Filter::Tag(_) => None,
+            Filter::Status(_) => Some(Column::Status),
+        }
+    }
+
+    pub fn matches(&self, info: &VenvInfo) -> bool {
+        match self {
+            Filter::Python(version) => {
+                let installed = &info.venv.python_version;
+                installed == version || installed.starts_with(&format!("{}.", version))
+            }
+            Filter::Status(status) => info
+                .status
+                .as_ref()
+                .is_some_and(|s| s.eq_ignore_ascii_case(status)),
+            Filter::Tag(tag) => info.venv.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("invalid filter '{}', expected key=value", s))?;
+        let value = value.trim().to_string();
+        match key.trim().to_lowercase().as_str() {
+            "python" => Ok(Filter::Python(value)),
+            "status" => Ok(Filter::Status(value)),
+            "tag" => Ok(Filter::Tag(value)),
+            other => Err(format!(
+                "unknown filter '{}', expected one of: python, status, tag",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct VenvInfo {
+    pub venv: Venv,
+    pub size: Option<u64>,
+    pub packages: Option<usize>,
+    pub created: Option<DateTime<Utc>>,
+    pub used: Option<DateTime<Utc>>,
+    pub status: Option<String>,
+}
+
+impl VenvInfo {
+    pub async fn collect(mut venv: Venv, columns: Vec<Column>) -> Self {
+        venv.set_python_version().await;
+        let metadata = Metadata::load(&venv.path);
+        let size = if columns.contains(&Column::Size) {
+            let path = venv.path.clone();
+            tokio::task::spawn_blocking(move || utils::dir_size(&path))
+                .await
+                .ok()
+        } else {
+            None
+        };
+        let packages = columns
+            .contains(&Column::Packages)
+            .then(|| venv.dist_info_dirs().len());
+        let created = metadata.created.or_else(|| {
+            fs::metadata(venv.path.join("pyvenv.cfg"))
+                .and_then(|m| m.modified())
+                .ok()
+                .map(DateTime::<Utc>::from)
+        });
+        let status = if columns.contains(&Column::Status) {
+            let issues = doctor::check(&venv).await;
+            let status = match issues.iter().map(|i| i.severity()).max() {
+                None => "ok",
+                Some(Severity::Warning) => "warning",
+                Some(Severity::Error) => "error",
+            };
+            Some(status.to_string())
+        } else {
+            None
+        };
+        VenvInfo {
+            venv,
+            size,
+            packages,
+            created,
+            used: metadata.last_used,
+            status,
+        }
+    }
+
+    pub fn last_activity(&self) -> Option<DateTime<Utc>> {
+        self.used.or(self.created)
+    }
+
+    pub fn is_stale(&self, cutoff: DateTime<Utc>) -> bool {
+        self.last_activity().is_none_or(|t| t < cutoff)
+    }
+
+    pub fn cell(&self, column: Column) -> String {
+        let time = |t: &Option<DateTime<Utc>>| t.as_ref().map(utils::format_time);
+        match column {
+            Column::Size => self.size.map(utils::format_size),
+            Column::Packages => self.packages.map(|p| p.to_string()),
+            Column::Created => time(&self.created),
+            Column::Used => time(&self.used),
+            Column::Path => Some(self.venv.path.display().to_string()),
+            Column::Status => self.status.clone(),
+            Column::Tags => Some(self.venv.tags.join(", ")).filter(|t| !t.is_empty()),
+            Column::Description => self.venv.description.clone(),
+        }
+        .unwrap_or_else(|| "-".to_string())
+    }
+}
+
+pub fn required_columns(columns: &[Column], sort: SortKey, filters: &[Filter]) -> Vec<Column> {
+    let mut required = columns.to_vec();
+    for column in sort
+        .column()
+        .into_iter()
+        .chain(filters.iter().filter_map(Filter::column))
+    {
+        if !required.contains(&column) {
+            required.push(column);
+        }
+    }
+    required
+}
+
+pub fn sort_infos(infos: &mut [VenvInfo], sort: SortKey) {
+    match sort {
+        SortKey::Name => infos.sort_by(|a, b| a.venv.name.cmp(&b.venv.name)),
+        SortKey::Size => infos.sort_by_key(|i| std::cmp::Reverse(i.size)),
+        SortKey::Created => infos.sort_by_key(|i| std::cmp::Reverse(i.created)),
+        SortKey::Used => infos.sort_by_key(|i| std::cmp::Reverse(i.used)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn info(name: &str, version: &str, size: u64) -> VenvInfo {
+        let mut venv = Venv::from_path(PathBuf::from(format!("/venvs/{}", name)));
+        venv.python_version = version.to_string();
+        VenvInfo {
+            venv,
+            size: Some(size),
+            packages: None,
+            created: None,
+            used: None,
+            status: Some("ok".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_column() {
+        assert_eq!("size".parse::<Column>(), Ok(Column::Size));
+        assert_eq!("Packages".parse::<Column>(), Ok(Column::Packages));
+        assert!("bogus".parse::<Column>().is_err());
+    }
+
+    #[test]
+    fn test_parse_sort_key() {
+        assert_eq!("used".parse::<SortKey>(), Ok(SortKey::Used));
+        assert!("path".parse::<SortKey>().is_err());
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(
+            "python=3.11".parse::<Filter>(),
+            Ok(Filter::Python("3.11".to_string()))
+        );
+        assert!("python".parse::<Filter>().is_err());
+        assert!("colour=red".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn test_filter_python() {
+        let filter = Filter::Python("3.11".to_string());
+        assert!(filter.matches(&info("a", "3.11.4", 0)));
+        assert!(filter.matches(&info("a", "3.11", 0)));
+        assert!(!filter.matches(&info("a", "3.110.1", 0)));
+        assert!(!filter.matches(&info("a", "3.12.0", 0)));
+    }
+
+    #[test]
+    fn test_filter_tag() {
+        let mut info = info("a", "3.11", 0);
+        info.venv.tags = vec!["gpu".to_string()];
+        assert!("tag=GPU".parse::<Filter>().unwrap().matches(&info));
+        assert!(!Filter::Tag("cpu".to_string()).matches(&info));
+        assert_eq!(info.cell(Column::Tags), "gpu");
+        assert_eq!(info.cell(Column::Description), "-");
+    }
+
+    #[test]
+    fn test_sort_infos() {
+        let mut infos = vec![
+            info("b", "3.11", 10),
+            info("a", "3.11", 30),
+            info("c", "3.11", 20),
+        ];
+        sort_infos(&mut infos, SortKey::Name);
+        let names: Vec<_> = infos.iter().map(|i| i.venv.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+        sort_infos(&mut infos, SortKey::Size);
+        let names: Vec<_> = infos.iter().map(|i| i.venv.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "c", "b"]);
+    }
+
+    #[test]
+    fn test_required_columns() {
+        let required = required_columns(
+            &[Column::Path, Column::Size],
+            SortKey::Size,
+            &[Filter::Status("ok".to_string())],
+        );
+        assert_eq!(required, vec![Column::Path, Column::Size, Column::Status]);
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let cutoff = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let before = DateTime::from_timestamp(1_600_000_000, 0);
+        let after = DateTime::from_timestamp(1_750_000_000, 0);
+        let mut info = info("a", "3.11", 0);
+        assert!(info.is_stale(cutoff));
+        info.created = before;
+        assert!(info.is_stale(cutoff));
+        info.used = after;
+        assert!(!info.is_stale(cutoff));
+        info.created = after;
+        info.used = before;
+        assert!(info.is_stale(cutoff));
+    }
+
+    #[test]
+    fn test_cell() {
+        let info = info("a", "3.11", 2048);
+        assert_eq!(info.cell(Column::Size), "2.0 KB");
+        assert_eq!(info.cell(Column::Packages), "-");
+        assert_eq!(info.cell(Column::Status), "ok");
+    }
+}
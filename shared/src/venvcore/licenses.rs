@@ -0,0 +1,447 @@
+use super::{
+    site_packages::{self, InstalledDistribution},
+    venv::Venv,
+};
+use crate::{constants::DIST_INFO_METADATA, settings::LicensePolicy};
+use std::{fmt, fs, str::FromStr};
+
+const UNKNOWN: &str = "UNKNOWN";
+const CLASSIFIER_PREFIX: &str = "License ::";
+const MAX_LICENSE_LEN: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Allowed,
+    Denied,
+    NotAllowed,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Allowed => write!(f, "allowed"),
+            Verdict::Denied => write!(f, "denied"),
+            Verdict::NotAllowed => write!(f, "not allowed"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PackageLicense {
+    pub name: String,
+    pub version: String,
+    pub license: String,
+    pub classifiers: Vec<String>,
+    pub verdict: Verdict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LicenseFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for LicenseFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "table" => Ok(LicenseFormat::Table),
+            "csv" => Ok(LicenseFormat::Csv),
+            "json" => Ok(LicenseFormat::Json),
+            other => Err(format!(
+                "Unknown format '{}', use table, csv or json",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct LicenseFields {
+    pub name: String,
+    pub version: String,
+    pub expression: Option<String>,
+    pub license: Option<String>,
+    pub classifiers: Vec<String>,
+}
+
+pub fn parse_metadata(content: &str) -> Option<LicenseFields> {
+    let mut fields = LicenseFields::default();
+    for (key, value) in site_packages::headers(content) {
+        match key.to_lowercase().as_str() {
+            "name" => fields.name = value,
+            "version" => fields.version = value,
+            "license-expression" => fields.expression = Some(value),
+            "license" => fields.license = Some(value),
+            "classifier" if value.starts_with(CLASSIFIER_PREFIX) => fields.classifiers.push(value),
+            _ => {}
+        }
+    }
+    (!fields.name.is_empty()).then_some(fields)
+}
+
+fn classifier_name(classifier: &str) -> &str {
+    classifier.rsplit("::").next().unwrap_or(classifier).trim()
+}
+
+pub fn resolve(fields: &LicenseFields) -> String {
+    if let Some(expression) = fields.expression.as_ref().filter(|e| !e.is_empty()) {
+        return expression.clone();
+    }
+    let license = fields
+        .license
+        .as_deref()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.eq_ignore_ascii_case(UNKNOWN));
+    if let Some(license) = license.filter(|l| !l.contains('\n') && l.len() <= MAX_LICENSE_LEN) {
+        return license.to_string();
+    }
+    let classifiers: Vec<&str> = fields
+        .classifiers
+        .iter()
+        .map(|c| classifier_name(c))
+        .filter(|c| *c != "OSI Approved")
+        .collect();
+    if !classifiers.is_empty() {
+        return classifiers.join("; ");
+    }
+    match license.and_then(|l| l.lines().next()) {
+        Some(line) if line.len() > MAX_LICENSE_LEN => {
+            format!(
+                "{}...",
+                line.chars().take(MAX_LICENSE_LEN).collect::<String>()
+            )
+        }
+        Some(line) => line.to_string(),
+        None => UNKNOWN.to_string(),
+    }
+}
+
+fn matches(pattern: &str, value: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    let value = value.trim().to_lowercase();
+    match pattern.strip_suffix('*') {
+        Some(prefix) => value.starts_with(prefix),
+        None => value == pattern,
+    }
+}
+
+fn rank(verdict: Verdict) -> u8 {
+    match verdict {
+        Verdict::Denied => 0,
+        Verdict::NotAllowed => 1,
+        Verdict::Allowed => 2,
+    }
+}
+
+fn term_verdict(policy: &LicensePolicy, term: &str) -> Verdict {
+    if policy.deny.iter().any(|p| matches(p, term)) {
+        Verdict::Denied
+    } else if !policy.allow.is_empty() && !policy.allow.iter().any(|p| matches(p, term)) {
+        Verdict::NotAllowed
+    } else {
+        Verdict::Allowed
+    }
+}
+
+struct Expression<'a> {
+    tokens: Vec<String>,
+    pos: usize,
+    policy: &'a LicensePolicy,
+}
+
+impl Expression<'_> {
+    fn peek(&self, words: &[&str]) -> bool {
+        self.tokens
+            .get(self.pos)
+            .is_some_and(|t| words.contains(&t.as_str()))
+    }
+
+    fn or(&mut self) -> Option<Verdict> {
+        let mut verdict = self.and()?;
+        while self.peek(&["OR", "or", ";"]) {
+            self.pos += 1;
+            let right = self.and()?;
+            verdict = if rank(right) > rank(verdict) {
+                right
+            } else {
+                verdict
+            };
+        }
+        Some(verdict)
+    }
+
+    fn and(&mut self) -> Option<Verdict> {
+        let mut verdict = self.with()?;
+        while self.peek(&["AND", "and"]) {
+            self.pos += 1;
+            let right = self.with()?;
+            verdict = if rank(right) < rank(verdict) {
+                right
+            } else {
+                verdict
+            };
+        }
+        Some(verdict)
+    }
+
+    fn with(&mut self) -> Option<Verdict> {
+        if self.peek(&["("]) {
+            self.pos += 1;
+            let verdict = self.or()?;
+            if !self.peek(&[")"]) {
+                return None;
+            }
+            self.pos += 1;
+            return Some(verdict);
+        }
+        let license = self.term()?;
+        if !self.peek(&["WITH"]) {
+            return Some(term_verdict(self.policy, &license));
+        }
+        self.pos += 1;
+        let exception = self.term()?;
+        let whole = term_verdict(self.policy, &format!("{} WITH {}", license, exception));
+        if whole == Verdict::Allowed {
+            return Some(whole);
+        }
+        let parts = [
+            term_verdict(self.policy, &license),
+            term_verdict(self.policy, &exception),
+        ];
+        parts.into_iter().min_by_key(|v| rank(*v))
+    }
+
+    fn term(&mut self) -> Option<String> {
+        let start = self.pos;
+        while self.pos < self.tokens.len()
+            && !self.peek(&["OR", "or", "AND", "and", "WITH", ";", "(", ")"])
+        {
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| self.tokens[start..self.pos].join(" "))
+    }
+}
+
+pub fn verdict(policy: &LicensePolicy, license: &str) -> Verdict {
+    let whole = term_verdict(policy, license);
+    if whole == Verdict::Denied || policy.allow.iter().any(|p| matches(p, license)) {
+        return whole;
+    }
+    let tokens: Vec<String> = license
+        .replace('(', " ( ")
+        .replace(')', " ) ")
+        .replace(';', " ; ")
+        .split_whitespace()
+        .map(str::to_string)
+        .collect();
+    let mut expression = Expression {
+        tokens,
+        pos: 0,
+        policy,
+    };
+    match expression.or() {
+        Some(verdict) if expression.pos == expression.tokens.len() => verdict,
+        _ => whole,
+    }
+}
+
+fn package_license(dist: &InstalledDistribution, policy: &LicensePolicy) -> PackageLicense {
+    let fields = fs::read_to_string(dist.dist_info.join(DIST_INFO_METADATA))
+        .ok()
+        .and_then(|content| parse_metadata(&content))
+        .unwrap_or_default();
+    let license = resolve(&fields);
+    PackageLicense {
+        verdict: verdict(policy, &license),
+        name: dist.name.clone(),
+        version: dist.version.clone(),
+        license,
+        classifiers: fields.classifiers,
+    }
+}
+
+pub fn inventory(venv: &Venv, policy: &LicensePolicy) -> Vec<PackageLicense> {
+    venv.distributions()
+        .iter()
+        .map(|dist| package_license(dist, policy))
+        .collect()
+}
+
+pub fn violations(packages: &[PackageLicense]) -> usize {
+    packages
+        .iter()
+        .filter(|p| p.verdict != Verdict::Allowed)
+        .count()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn to_csv(packages: &[PackageLicense]) -> String {
+    let mut csv = String::from("name,version,license,verdict\n");
+    for package in packages {
+        let row = [
+            package.name.clone(),
+            package.version.clone(),
+            package.license.clone(),
+            package.verdict.to_string(),
+        ];
+        let row: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn to_json(packages: &[PackageLicense]) -> Result<String, String> {
+    serde_json::to_string_pretty(packages).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allow: &[&str], deny: &[&str]) -> LicensePolicy {
+        LicensePolicy {
+            allow: allow.iter().map(|s| s.to_string()).collect(),
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("CSV".parse::<LicenseFormat>(), Ok(LicenseFormat::Csv));
+        assert_eq!("json".parse::<LicenseFormat>(), Ok(LicenseFormat::Json));
+        assert!("xml".parse::<LicenseFormat>().is_err());
+    }
+
+    #[test]
+    fn test_parse_metadata_and_resolve() {
+        let content = "Metadata-Version: 2.1\nName: requests\nVersion: 2.32.3\nLicense: Apache 2.0\nClassifier: License :: OSI Approved :: Apache Software License\nClassifier: Programming Language :: Python\n\nLicense: body text\n";
+        let fields = parse_metadata(content).unwrap();
+        assert_eq!(fields.name, "requests");
+        assert_eq!(fields.version, "2.32.3");
+        assert_eq!(fields.classifiers.len(), 1);
+        assert_eq!(resolve(&fields), "Apache 2.0");
+
+        let expression =
+            "Name: attrs\nVersion: 24.2.0\nLicense-Expression: MIT\nLicense: UNKNOWN\n";
+        assert_eq!(resolve(&parse_metadata(expression).unwrap()), "MIT");
+
+        let long = "Name: numpy\nVersion: 2.1.0\nLicense: Copyright (c) 2005-2024, NumPy Developers.\n        All rights reserved.\nClassifier: License :: OSI Approved :: BSD License\n";
+        let fields = parse_metadata(long).unwrap();
+        assert!(fields.license.unwrap().contains("All rights reserved."));
+        assert_eq!(resolve(&parse_metadata(long).unwrap()), "BSD License");
+
+        assert_eq!(
+            resolve(&parse_metadata("Name: six\nVersion: 1.16.0\n").unwrap()),
+            "UNKNOWN"
+        );
+        assert!(parse_metadata("Version: 1.0\n").is_none());
+    }
+
+    #[test]
+    fn test_verdict() {
+        let empty = LicensePolicy::default();
+        assert_eq!(verdict(&empty, "GPL-3.0-only"), Verdict::Allowed);
+
+        let policy = policy(&["MIT", "BSD*", "Apache-2.0"], &["GPL-3.0*"]);
+        assert_eq!(verdict(&policy, "mit"), Verdict::Allowed);
+        assert_eq!(verdict(&policy, "BSD License"), Verdict::Allowed);
+        assert_eq!(verdict(&policy, "(MIT OR Apache-2.0)"), Verdict::Allowed);
+        assert_eq!(verdict(&policy, "MPL-2.0"), Verdict::NotAllowed);
+        assert_eq!(verdict(&policy, "UNKNOWN"), Verdict::NotAllowed);
+    }
+
+    #[test]
+    fn test_verdict_expressions() {
+        let strict = policy(&["MIT", "BSD*", "Apache-2.0"], &["GPL-3.0*"]);
+        assert_eq!(verdict(&strict, "MIT OR GPL-3.0-only"), Verdict::Allowed);
+        assert_eq!(
+            verdict(&strict, "MPL-2.0 OR GPL-3.0-only"),
+            Verdict::NotAllowed
+        );
+        assert_eq!(
+            verdict(&strict, "GPL-3.0-only or GPL-3.0+"),
+            Verdict::Denied
+        );
+        assert_eq!(verdict(&strict, "MIT AND Apache-2.0"), Verdict::Allowed);
+        assert_eq!(verdict(&strict, "MIT AND MPL-2.0"), Verdict::NotAllowed);
+        assert_eq!(verdict(&strict, "MIT AND GPL-3.0-only"), Verdict::Denied);
+        assert_eq!(
+            verdict(
+                &strict,
+                "(MIT OR MPL-2.0) AND (BSD-3-Clause OR GPL-3.0-only)"
+            ),
+            Verdict::Allowed
+        );
+        assert_eq!(
+            verdict(&strict, "Apache-2.0 WITH LLVM-exception"),
+            Verdict::NotAllowed
+        );
+        assert_eq!(
+            verdict(&strict, "MIT License; BSD License"),
+            Verdict::Allowed
+        );
+        assert_eq!(verdict(&strict, "(MIT"), Verdict::NotAllowed);
+
+        let exceptions = policy(&["Apache-2.0 WITH LLVM-exception", "MIT"], &[]);
+        assert_eq!(
+            verdict(&exceptions, "Apache-2.0 WITH LLVM-exception OR MIT"),
+            Verdict::Allowed
+        );
+        assert_eq!(
+            verdict(&exceptions, "Apache-2.0 WITH LLVM-exception AND MIT"),
+            Verdict::Allowed
+        );
+    }
+
+    #[test]
+    fn test_inventory_and_output() {
+        let root = std::env::temp_dir().join(format!("pypilot_licenses_{}", std::process::id()));
+        let mut venv = Venv::new("licenses".to_string(), "".to_string(), vec![], false);
+        venv.path = root.join("licenses");
+        let sp = venv.path.join("lib/python3.11/site-packages");
+        for (dir, metadata) in [
+            (
+                "six-1.16.0.dist-info",
+                "Name: six\nVersion: 1.16.0\nLicense: MIT\n",
+            ),
+            (
+                "gplpkg-1.0.dist-info",
+                "Name: GplPkg\nVersion: 1.0\nLicense-Expression: GPL-3.0-or-later\n",
+            ),
+            ("broken-1.0.dist-info", ""),
+        ] {
+            fs::create_dir_all(sp.join(dir)).unwrap();
+            fs::write(sp.join(dir).join(DIST_INFO_METADATA), metadata).unwrap();
+        }
+        let packages = inventory(&venv, &policy(&["MIT"], &[]));
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(packages.len(), 3);
+        assert_eq!(packages[0].name, "broken");
+        assert_eq!(packages[0].license, "UNKNOWN");
+        assert_eq!(packages[1].name, "GplPkg");
+        assert_eq!(packages[1].verdict, Verdict::NotAllowed);
+        assert_eq!(packages[2].verdict, Verdict::Allowed);
+        assert_eq!(violations(&packages), 2);
+
+        assert_eq!(
+            to_csv(&packages),
+            "name,version,license,verdict\nbroken,1.0,UNKNOWN,not allowed\nGplPkg,1.0,GPL-3.0-or-later,not allowed\nsix,1.16.0,MIT,allowed\n"
+        );
+        assert_eq!(csv_field("MIT, \"BSD\""), "\"MIT, \"\"BSD\"\"\"");
+        let json = to_json(&packages).unwrap();
+        assert!(json.contains(r#""verdict": "not_allowed""#));
+    }
+}
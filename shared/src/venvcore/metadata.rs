@@ -0,0 +1,72 @@
+use crate::constants::METADATA_FILE;
+use chrono::{DateTime, Utc};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wheelhouse: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub offline: bool,
+}
+
+impl Metadata {
+    pub fn load(venv_path: &Path) -> Self {
+        fs::read_to_string(venv_path.join(METADATA_FILE))
+            .ok()
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, venv_path: &Path) -> Result<(), String> {
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(venv_path.join(METADATA_FILE), content)
+            .map_err(|e| format!("Error writing metadata: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_missing() {
+        let metadata = Metadata::load(Path::new("/pypilot/does/not/exist"));
+        assert_eq!(metadata, Metadata::default());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("pypilot_metadata_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let metadata = Metadata {
+            created: DateTime::from_timestamp(1_700_000_000, 0),
+            last_used: None,
+            tags: vec!["gpu".to_string()],
+            description: Some("Training jobs".to_string()),
+            index_profile: Some("corp".to_string()),
+            index_url: None,
+            wheelhouse: Some(PathBuf::from("/srv/wheels")),
+            offline: true,
+        };
+        metadata.save(&dir).unwrap();
+        let loaded = Metadata::load(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(loaded, metadata);
+    }
+}
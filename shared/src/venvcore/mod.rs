@@ -1,3 +1,21 @@
+pub mod audit;
+pub mod credentials;
+pub mod diff;
+pub mod doctor;
+pub mod info;
+pub mod licenses;
+pub mod metadata;
+pub mod outdated;
+pub mod pack;
+pub mod picker;
+pub mod site_packages;
+pub mod snapshot;
+#[cfg(test)]
+pub mod testutil;
+pub mod tree;
+pub mod usage;
 pub mod uv;
+pub mod uvarchive;
 pub mod venv;
 pub mod venvmanager;
+pub mod wheelhouse;
@@ -0,0 +1,290 @@
+use super::{credentials, diff, venv::Venv};
+use crate::{
+    processes, requirement::normalize_name, settings::IndexSettings, uv, version::Version,
+};
+use colored::Colorize;
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    Major,
+    Minor,
+    Patch,
+    Other,
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bump::Major => write!(f, "major"),
+            Bump::Minor => write!(f, "minor"),
+            Bump::Patch => write!(f, "patch"),
+            Bump::Other => write!(f, "other"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outdated {
+    pub name: String,
+    pub installed: Version,
+    pub latest: Version,
+    pub bump: Bump,
+}
+
+impl Outdated {
+    pub fn requirement(&self) -> String {
+        format!("{}=={}", self.name, self.latest)
+    }
+}
+
+pub fn bump(installed: &Version, latest: &Version) -> Bump {
+    let part = |v: &Version, i: usize| v.release.get(i).copied().unwrap_or(0);
+    if part(installed, 0) != part(latest, 0) {
+        Bump::Major
+    } else if part(installed, 1) != part(latest, 1) {
+        Bump::Minor
+    } else if part(installed, 2) != part(latest, 2) {
+        Bump::Patch
+    } else {
+        Bump::Other
+    }
+}
+
+pub fn parse_dist_filename(filename: &str) -> Option<(String, Version)> {
+    let (name, version) = if let Some(stem) = filename.strip_suffix(".whl") {
+        let mut parts = stem.split('-');
+        (parts.next()?, parts.next()?)
+    } else {
+        let stem = [".tar.gz", ".zip", ".tar.bz2", ".tgz"]
+            .iter()
+            .find_map(|ext| filename.strip_suffix(ext))?;
+        stem.rsplit_once('-')?
+    };
+    Some((normalize_name(name), version.parse().ok()?))
+}
+
+pub fn local_versions(find_links: &[String]) -> BTreeMap<String, Vec<Version>> {
+    let mut versions: BTreeMap<String, Vec<Version>> = BTreeMap::new();
+    for link in find_links {
+        let dir = link.strip_prefix("file://").unwrap_or(link);
+        if dir.contains("://") {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(Path::new(dir)) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let filename = entry.file_name().to_string_lossy().to_string();
+            if let Some((name, version)) = parse_dist_filename(&filename) {
+                versions.entry(name).or_default().push(version);
+            }
+        }
+    }
+    versions
+}
+
+#[derive(serde::Deserialize)]
+struct UvOutdated {
+    name: String,
+    latest_version: String,
+}
+
+pub fn parse_uv_outdated(json: &str) -> Result<BTreeMap<String, Vec<Version>>, String> {
+    let entries: Vec<UvOutdated> =
+        serde_json::from_str(json).map_err(|e| format!("Invalid uv output: {}", e))?;
+    let mut versions: BTreeMap<String, Vec<Version>> = BTreeMap::new();
+    for entry in entries {
+        if let Ok(version) = entry.latest_version.parse() {
+            versions
+                .entry(normalize_name(&entry.name))
+                .or_default()
+                .push(version);
+        }
+    }
+    Ok(versions)
+}
+
+async fn index_versions(
+    venv: &Venv,
+    index: &IndexSettings,
+) -> Result<BTreeMap<String, Vec<Version>>, String> {
+    let index = credentials::with_credentials(index);
+    let mut args = vec![
+        "pip".to_string(),
+        "list".to_string(),
+        "--outdated".to_string(),
+        "--format".to_string(),
+        "json".to_string(),
+        "--python".to_string(),
+        venv.python().display().to_string(),
+    ];
+    args.extend(index.args());
+    let output = processes::capture_output_with_env(&uv::uv_cmd(), &args, &index.env()).await?;
+    parse_uv_outdated(&output)
+}
+
+pub fn find_outdated(
+    installed: &BTreeMap<String, (String, String)>,
+    available: &BTreeMap<String, Vec<Version>>,
+) -> Vec<Outdated> {
+    let mut outdated: Vec<Outdated> = installed
+        .iter()
+        .filter_map(|(key, (name, version))| {
+            let installed: Version = version.parse().ok()?;
+            let latest = available
+                .get(key)?
+                .iter()
+                .filter(|v| installed.is_prerelease() || !v.is_prerelease())
+                .max()?
+                .clone();
+            (latest > installed).then(|| Outdated {
+                name: name.clone(),
+                bump: bump(&installed, &latest),
+                installed,
+                latest,
+            })
+        })
+        .collect();
+    outdated.sort_by_key(|o| normalize_name(&o.name));
+    outdated
+}
+
+pub async fn check(venv: &Venv) -> Result<Vec<Outdated>, String> {
+    let index = venv.index()?;
+    let mut available = local_versions(&index.find_links);
+    if !index.no_index {
+        match index_versions(venv, &index).await {
+            Ok(versions) => {
+                for (name, found) in versions {
+                    available.entry(name).or_default().extend(found);
+                }
+            }
+            Err(e) if available.is_empty() => return Err(e),
+            Err(e) => eprintln!(
+                "{}",
+                format!(
+                    "Could not query the package index, using find-links only: {}",
+                    e
+                )
+                .yellow()
+            ),
+        }
+    }
+    Ok(find_outdated(&diff::installed(venv), &available))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_bump() {
+        assert_eq!(bump(&v("1.2.3"), &v("2.0.0")), Bump::Major);
+        assert_eq!(bump(&v("1.2.3"), &v("1.3")), Bump::Minor);
+        assert_eq!(bump(&v("1.2.3"), &v("1.2.4")), Bump::Patch);
+        assert_eq!(bump(&v("1.2.3"), &v("1.2.3.post1")), Bump::Other);
+        assert_eq!(Bump::Minor.to_string(), "minor");
+    }
+
+    #[test]
+    fn test_parse_dist_filename() {
+        assert_eq!(
+            parse_dist_filename("typing_extensions-4.12.2-py3-none-any.whl"),
+            Some(("typing-extensions".to_string(), v("4.12.2")))
+        );
+        assert_eq!(
+            parse_dist_filename("numpy-2.1.0-cp311-cp311-manylinux_2_17_x86_64.whl"),
+            Some(("numpy".to_string(), v("2.1.0")))
+        );
+        assert_eq!(
+            parse_dist_filename("python-dateutil-2.9.0.tar.gz"),
+            Some(("python-dateutil".to_string(), v("2.9.0")))
+        );
+        assert_eq!(parse_dist_filename("requirements.txt"), None);
+    }
+
+    #[test]
+    fn test_local_versions_and_find_outdated() {
+        let dir = std::env::temp_dir().join(format!("pypilot_outdated_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for file in [
+            "six-1.16.0-py2.py3-none-any.whl",
+            "six-1.17.0-py2.py3-none-any.whl",
+            "requests-2.32.0-py3-none-any.whl",
+            "requests-3.0.0rc1-py3-none-any.whl",
+            "numpy-1.26.0.tar.gz",
+        ] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let available = local_versions(&[
+            format!("file://{}", dir.display()),
+            "https://example.com/wheels".to_string(),
+        ]);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(available["six"].len(), 2);
+
+        let installed: BTreeMap<String, (String, String)> = [
+            ("six", "1.16.0"),
+            ("requests", "2.31.0"),
+            ("numpy", "1.26.0"),
+            ("pandas", "2.1.0"),
+        ]
+        .iter()
+        .map(|(n, v)| (n.to_string(), (n.to_string(), v.to_string())))
+        .collect();
+        let outdated = find_outdated(&installed, &available);
+        assert_eq!(
+            outdated,
+            vec![
+                Outdated {
+                    name: "requests".to_string(),
+                    installed: v("2.31.0"),
+                    latest: v("2.32.0"),
+                    bump: Bump::Minor,
+                },
+                Outdated {
+                    name: "six".to_string(),
+                    installed: v("1.16.0"),
+                    latest: v("1.17.0"),
+                    bump: Bump::Minor,
+                },
+            ]
+        );
+        assert_eq!(outdated[0].requirement(), "requests==2.32.0");
+    }
+
+    #[test]
+    fn test_parse_uv_outdated() {
+        let json = r#"[{"name":"Requests","version":"2.31.0","latest_version":"2.32.3","latest_filetype":"wheel"}]"#;
+        let versions = parse_uv_outdated(json).unwrap();
+        assert_eq!(versions["requests"], vec![v("2.32.3")]);
+        assert!(parse_uv_outdated("not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_check_wheelhouse() {
+        let root =
+            std::env::temp_dir().join(format!("pypilot_outdated_check_{}", std::process::id()));
+        let wheels = root.join("wheels");
+        fs::create_dir_all(&wheels).unwrap();
+        fs::write(wheels.join("six-1.17.0-py2.py3-none-any.whl"), "").unwrap();
+        let mut venv = Venv::new("check".to_string(), "".to_string(), vec![], false);
+        venv.path = root.join("check");
+        venv.wheelhouse = Some(wheels);
+        fs::create_dir_all(
+            venv.path
+                .join("lib/python3.11/site-packages/six-1.16.0.dist-info"),
+        )
+        .unwrap();
+        let outdated = check(&venv).await;
+        fs::remove_dir_all(&root).unwrap();
+        let outdated = outdated.unwrap();
+        assert_eq!(outdated.len(), 1);
+        assert_eq!(outdated[0].latest, v("1.17.0"));
+    }
+}
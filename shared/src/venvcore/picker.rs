@@ -0,0 +1,323 @@
+use super::info::{Column, VenvInfo};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Print, SetAttribute},
+    terminal::{self, ClearType},
+};
+use std::io::{self, Write};
+
+const MAX_VISIBLE: usize = 10;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Action {
+    Continue,
+    Select(Vec<usize>),
+    Cancel,
+}
+
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut last: Option<usize> = None;
+    let mut pos = 0;
+    for q in query.to_lowercase().chars() {
+        let found = candidate[pos..].iter().position(|c| *c == q)? + pos;
+        score += 1;
+        if found == 0 {
+            score += 8;
+        } else if matches!(candidate[found - 1], '-' | '_' | '.') {
+            score += 4;
+        }
+        if last.is_some_and(|l| l + 1 == found) {
+            score += 5;
+        }
+        score -= (found - pos) as i64;
+        last = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+pub fn fuzzy_filter(query: &str, names: &[&str]) -> Vec<usize> {
+    let mut matches: Vec<(i64, usize)> = names
+        .iter()
+        .enumerate()
+        .filter_map(|(i, name)| fuzzy_score(query, name).map(|s| (s, i)))
+        .collect();
+    matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    matches.into_iter().map(|(_, i)| i).collect()
+}
+
+pub struct PickerState {
+    pub query: String,
+    pub selected: usize,
+    pub matches: Vec<usize>,
+    pub marked: Vec<usize>,
+    pub multi: bool,
+}
+
+impl PickerState {
+    pub fn new(names: &[&str], multi: bool) -> Self {
+        PickerState {
+            query: String::new(),
+            selected: 0,
+            matches: fuzzy_filter("", names),
+            marked: Vec::new(),
+            multi,
+        }
+    }
+
+    pub fn handle(&mut self, key: KeyEvent, names: &[&str]) -> Action {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Action::Cancel,
+            KeyCode::Char('c') if ctrl => return Action::Cancel,
+            KeyCode::Enter => {
+                if !self.marked.is_empty() {
+                    return Action::Select(self.marked.clone());
+                }
+                return match self.matches.get(self.selected) {
+                    Some(index) => Action::Select(vec![*index]),
+                    None => Action::Continue,
+                };
+            }
+            KeyCode::Char(' ') if self.multi => self.toggle(),
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Char('p') if ctrl => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => self.move_down(),
+            KeyCode::Char('n') if ctrl => self.move_down(),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.refilter(names);
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.refilter(names);
+            }
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    fn toggle(&mut self) {
+        let Some(index) = self.matches.get(self.selected) else {
+            return;
+        };
+        match self.marked.iter().position(|m| m == index) {
+            Some(pos) => {
+                self.marked.remove(pos);
+            }
+            None => self.marked.push(*index),
+        }
+    }
+
+    fn move_down(&mut self) {
+        if self.selected + 1 < self.matches.len() {
+            self.selected += 1;
+        }
+    }
+
+    fn refilter(&mut self, names: &[&str]) {
+        self.matches = fuzzy_filter(&self.query, names);
+        self.selected = 0;
+    }
+}
+
+struct RawModeGuard;
+
+impl RawModeGuard {
+    fn enable() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(RawModeGuard)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub fn pick(venvs: &[VenvInfo], method: &str) -> Result<usize, String> {
+    run(venvs, method, false).map(|selected| selected[0])
+}
+
+pub fn pick_many(venvs: &[VenvInfo], method: &str) -> Result<Vec<usize>, String> {
+    run(venvs, method, true)
+}
+
+fn run(venvs: &[VenvInfo], method: &str, multi: bool) -> Result<Vec<usize>, String> {
+    let names: Vec<&str> = venvs.iter().map(|v| v.venv.name.as_str()).collect();
+    let mut state = PickerState::new(&names, multi);
+    let _guard = RawModeGuard::enable().map_err(|e| e.to_string())?;
+    loop {
+        render(&mut io::stdout(), venvs, &state, method).map_err(|e| e.to_string())?;
+        let key = match event::read().map_err(|e| e.to_string())? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => key,
+            _ => continue,
+        };
+        match state.handle(key, &names) {
+            Action::Continue => {}
+            Action::Select(selected) => return Ok(selected),
+            Action::Cancel => return Err("Cancelled by user".to_string()),
+        }
+    }
+}
+
+fn render<W: Write>(
+    out: &mut W,
+    venvs: &[VenvInfo],
+    state: &PickerState,
+    method: &str,
+) -> io::Result<()> {
+    queue!(
+        out,
+        cursor::MoveTo(0, 0),
+        terminal::Clear(ClearType::All),
+        Print(format!(
+            "Select a virtual environment to {} (type to filter, Up/Down to move, {}Enter to select, Esc to cancel)\r\n",
+            method,
+            if state.multi { "Space to mark, " } else { "" }
+        )),
+        Print(format!("> {}\r\n\r\n", state.query)),
+    )?;
+    let start = state.selected.saturating_sub(MAX_VISIBLE - 1);
+    for (pos, index) in state
+        .matches
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(MAX_VISIBLE)
+    {
+        let venv = &venvs[*index].venv;
+        let mark = match (state.multi, state.marked.contains(index)) {
+            (false, _) => "",
+            (true, true) => "[x] ",
+            (true, false) => "[ ] ",
+        };
+        let line = format!("{}{} ({})", mark, venv.name, venv.python_version);
+        if pos == state.selected {
+            queue!(
+                out,
+                SetAttribute(Attribute::Reverse),
+                Print(format!("> {}", line)),
+                SetAttribute(Attribute::Reset),
+                Print("\r\n")
+            )?;
+        } else {
+            queue!(out, Print(format!("  {}\r\n", line)))?;
+        }
+    }
+    if state.matches.is_empty() {
+        queue!(out, Print("  No matching environments\r\n"))?;
+    }
+    if let Some(index) = state.matches.get(state.selected) {
+        let info = &venvs[*index];
+        queue!(out, Print("\r\n"))?;
+        for column in [Column::Path, Column::Created, Column::Used] {
+            queue!(
+                out,
+                Print(format!(
+                    "  {:<10} {}\r\n",
+                    column.header(),
+                    info.cell(column)
+                ))
+            )?;
+        }
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("mlb", "ml-base").is_some());
+        assert!(fuzzy_score("xyz", "ml-base").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert!(fuzzy_score("ml", "ml-base") > fuzzy_score("ml", "html"));
+    }
+
+    #[test]
+    fn test_fuzzy_filter() {
+        let names = ["scratch", "ml-base", "html-tools", "data"];
+        assert_eq!(fuzzy_filter("ml", &names), vec![1, 2]);
+        assert_eq!(fuzzy_filter("", &names), vec![0, 1, 2, 3]);
+        assert!(fuzzy_filter("zz", &names).is_empty());
+    }
+
+    #[test]
+    fn test_picker_state_filter_and_select() {
+        let names = ["scratch", "ml-base", "data"];
+        let mut state = PickerState::new(&names, false);
+        assert_eq!(
+            state.handle(key(KeyCode::Char('d')), &names),
+            Action::Continue
+        );
+        assert_eq!(state.matches, vec![2]);
+        assert_eq!(
+            state.handle(key(KeyCode::Enter), &names),
+            Action::Select(vec![2])
+        );
+    }
+
+    #[test]
+    fn test_picker_state_navigation() {
+        let names = ["a", "b", "c"];
+        let mut state = PickerState::new(&names, false);
+        state.handle(key(KeyCode::Down), &names);
+        state.handle(key(KeyCode::Down), &names);
+        state.handle(key(KeyCode::Down), &names);
+        assert_eq!(state.selected, 2);
+        state.handle(key(KeyCode::Up), &names);
+        assert_eq!(
+            state.handle(key(KeyCode::Enter), &names),
+            Action::Select(vec![1])
+        );
+    }
+
+    #[test]
+    fn test_picker_state_backspace_and_cancel() {
+        let names = ["a", "b"];
+        let mut state = PickerState::new(&names, false);
+        state.handle(key(KeyCode::Char('z')), &names);
+        assert!(state.matches.is_empty());
+        assert_eq!(state.handle(key(KeyCode::Enter), &names), Action::Continue);
+        state.handle(key(KeyCode::Backspace), &names);
+        assert_eq!(state.matches.len(), 2);
+        assert_eq!(state.handle(key(KeyCode::Esc), &names), Action::Cancel);
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(state.handle(ctrl_c, &names), Action::Cancel);
+    }
+
+    #[test]
+    fn test_picker_state_multi_select() {
+        let names = ["a", "b", "c"];
+        let mut state = PickerState::new(&names, true);
+        state.handle(key(KeyCode::Char(' ')), &names);
+        state.handle(key(KeyCode::Down), &names);
+        state.handle(key(KeyCode::Down), &names);
+        state.handle(key(KeyCode::Char(' ')), &names);
+        state.handle(key(KeyCode::Up), &names);
+        state.handle(key(KeyCode::Char(' ')), &names);
+        state.handle(key(KeyCode::Char(' ')), &names);
+        assert_eq!(state.query, "");
+        assert_eq!(
+            state.handle(key(KeyCode::Enter), &names),
+            Action::Select(vec![0, 2])
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use crate::{
+    constants::DIST_INFO_METADATA,
+    requirement::{normalize_name, Requirement},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub const RECORD_FILE: &str = "RECORD";
+pub const DIRECT_URL_FILE: &str = "direct_url.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    Index,
+    Editable(String),
+    Vcs(String),
+    Url(String),
+}
+
+impl Origin {
+    pub fn is_editable(&self) -> bool {
+        matches!(self, Origin::Editable(_))
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Origin::Index => None,
+            Origin::Editable(url) | Origin::Vcs(url) | Origin::Url(url) => Some(url),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstalledDistribution {
+    pub name: String,
+    pub version: String,
+    pub summary: Option<String>,
+    pub requires: Vec<Requirement>,
+    pub origin: Origin,
+    pub dist_info: PathBuf,
+}
+
+impl InstalledDistribution {
+    pub fn from_dist_info(dist_info: &Path) -> Option<Self> {
+        let stem = dist_info.file_stem()?.to_str()?;
+        let (dir_name, dir_version) = stem.split_once('-')?;
+        let headers = fs::read_to_string(dist_info.join(DIST_INFO_METADATA))
+            .map(|content| headers(&content))
+            .unwrap_or_default();
+        let field = |key: &str| {
+            headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v.clone())
+                .filter(|v| !v.is_empty())
+        };
+        let origin = fs::read_to_string(dist_info.join(DIRECT_URL_FILE))
+            .map(|content| parse_direct_url(&content))
+            .unwrap_or(Origin::Index);
+        Some(InstalledDistribution {
+            name: field("Name").unwrap_or_else(|| dir_name.to_string()),
+            version: field("Version").unwrap_or_else(|| dir_version.to_string()),
+            summary: field("Summary"),
+            requires: headers
+                .iter()
+                .filter(|(k, _)| k.eq_ignore_ascii_case("Requires-Dist"))
+                .filter_map(|(_, v)| v.parse().ok())
+                .collect(),
+            origin,
+            dist_info: dist_info.to_path_buf(),
+        })
+    }
+
+    pub fn requirement(&self) -> String {
+        match &self.origin {
+            Origin::Index => format!("{}=={}", self.name, self.version),
+            Origin::Editable(url) => format!("-e {}", url),
+            Origin::Vcs(url) | Origin::Url(url) => format!("{} @ {}", self.name, url),
+        }
+    }
+
+    pub fn key(&self) -> String {
+        normalize_name(&self.name)
+    }
+
+    pub fn site_packages(&self) -> &Path {
+        self.dist_info.parent().unwrap_or(&self.dist_info)
+    }
+
+    pub fn files(&self) -> Vec<PathBuf> {
+        fs::read_to_string(self.dist_info.join(RECORD_FILE))
+            .map(|record| {
+                record_paths(&record)
+                    .iter()
+                    .map(|path| self.site_packages().join(path))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+pub fn headers(content: &str) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in content.lines().take_while(|line| !line.trim().is_empty()) {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push('\n');
+                value.push_str(line.trim());
+            }
+        } else if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    headers
+}
+
+pub fn record_paths(record: &str) -> Vec<String> {
+    record
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.rsplitn(3, ',');
+            let (_, _, path) = (fields.next()?, fields.next()?, fields.next()?);
+            let path = path.trim_matches('"').replace('\\', "/");
+            (!path.is_empty()).then_some(path)
+        })
+        .collect()
+}
+
+#[derive(serde::Deserialize)]
+struct DirectUrl {
+    url: String,
+    #[serde(default)]
+    subdirectory: Option<String>,
+    #[serde(default)]
+    dir_info: Option<DirInfo>,
+    #[serde(default)]
+    vcs_info: Option<VcsInfo>,
+}
+
+#[derive(serde::Deserialize)]
+struct VcsInfo {
+    vcs: String,
+    #[serde(default)]
+    commit_id: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct DirInfo {
+    #[serde(default)]
+    editable: bool,
+}
+
+pub fn parse_direct_url(content: &str) -> Origin {
+    let Ok(direct_url) = serde_json::from_str::<DirectUrl>(content) else {
+        return Origin::Index;
+    };
+    let mut url = direct_url.url;
+    if direct_url.dir_info.is_some_and(|d| d.editable) {
+        return Origin::Editable(url);
+    }
+    let vcs = direct_url.vcs_info.map(|vcs| {
+        if !url.starts_with(&format!("{}+", vcs.vcs)) {
+            url = format!("{}+{}", vcs.vcs, url);
+        }
+        if let Some(commit) = vcs.commit_id {
+            url = format!("{}@{}", url, commit);
+        }
+    });
+    if let Some(subdirectory) = direct_url.subdirectory {
+        url = format!("{}#subdirectory={}", url, subdirectory);
+    }
+    match vcs {
+        Some(()) => Origin::Vcs(url),
+        None => Origin::Url(url),
+    }
+}
+
+fn python_dir(prefix: &Path) -> Option<String> {
+    let content = fs::read_to_string(prefix.join("pyvenv.cfg")).ok()?;
+    let version = content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        matches!(key.trim(), "version_info" | "version").then(|| value.trim().to_string())
+    })?;
+    let mut parts = version.split('.');
+    Some(format!("python{}.{}", parts.next()?, parts.next()?))
+}
+
+pub fn site_packages_dirs(prefix: &Path) -> Vec<PathBuf> {
+    let windows = prefix.join("Lib").join("site-packages");
+    if windows.is_dir() {
+        return vec![windows];
+    }
+    if let Some(dir) = python_dir(prefix)
+        .map(|python| prefix.join("lib").join(python).join("site-packages"))
+        .filter(|dir| dir.is_dir())
+    {
+        return vec![dir];
+    }
+    let Ok(entries) = fs::read_dir(prefix.join("lib")) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|e| e.path().join("site-packages"))
+        .filter(|p| p.is_dir())
+        .collect()
+}
+
+pub fn dist_info_dirs(prefix: &Path) -> Vec<PathBuf> {
+    site_packages_dirs(prefix)
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.filter_map(Result::ok))
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "dist-info") && p.is_dir())
+        .collect()
+}
+
+pub fn distributions(prefix: &Path) -> Vec<InstalledDistribution> {
+    let mut distributions: Vec<InstalledDistribution> = dist_info_dirs(prefix)
+        .iter()
+        .filter_map(|dir| InstalledDistribution::from_dist_info(dir))
+        .collect();
+    distributions.sort_by(|a, b| a.key().cmp(&b.key()).then(a.version.cmp(&b.version)));
+    distributions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("pypilot_site_{}_{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_headers() {
+        let headers = headers(
+            "Name: numpy\nLicense: Copyright\n        All rights reserved.\nRequires-Dist: a\n\nName: body\n",
+        );
+        assert_eq!(headers.len(), 3);
+        assert_eq!(headers[1].1, "Copyright\nAll rights reserved.");
+    }
+
+    #[test]
+    fn test_parse_direct_url() {
+        assert_eq!(
+            parse_direct_url(r#"{"url": "file:///src/app", "dir_info": {"editable": true}}"#),
+            Origin::Editable("file:///src/app".to_string())
+        );
+        assert_eq!(
+            parse_direct_url(
+                r#"{"url": "https://github.com/a/b.git", "vcs_info": {"vcs": "git", "commit_id": "abc"}}"#
+            ),
+            Origin::Vcs("git+https://github.com/a/b.git@abc".to_string())
+        );
+        assert_eq!(
+            parse_direct_url(
+                r#"{"url": "https://github.com/a/b.git", "subdirectory": "pkg", "vcs_info": {"vcs": "git"}}"#
+            ),
+            Origin::Vcs("git+https://github.com/a/b.git#subdirectory=pkg".to_string())
+        );
+        let url = parse_direct_url(r#"{"url": "https://x/p.whl", "archive_info": {}}"#);
+        assert_eq!(url.url(), Some("https://x/p.whl"));
+        assert!(!url.is_editable());
+        assert_eq!(parse_direct_url("broken"), Origin::Index);
+    }
+
+    #[test]
+    fn test_site_packages_dirs() {
+        let prefix = temp_dir("layout");
+        fs::create_dir_all(prefix.join("lib/python3.12/site-packages")).unwrap();
+        fs::create_dir_all(prefix.join("lib/python3.11/site-packages")).unwrap();
+        fs::write(
+            prefix.join("pyvenv.cfg"),
+            "home = /usr/bin\nversion_info = 3.11.4\n",
+        )
+        .unwrap();
+        let dirs = site_packages_dirs(&prefix);
+        fs::remove_file(prefix.join("pyvenv.cfg")).unwrap();
+        let scanned = site_packages_dirs(&prefix);
+        fs::remove_dir_all(&prefix).unwrap();
+        assert_eq!(dirs, vec![prefix.join("lib/python3.11/site-packages")]);
+        assert_eq!(scanned.len(), 2);
+    }
+
+    #[test]
+    fn test_distributions() {
+        let prefix = temp_dir("dists");
+        let sp = prefix.join("lib/python3.11/site-packages");
+        let requests = sp.join("requests-2.32.3.dist-info");
+        let app = sp.join("my_app-0.1.0.dist-info");
+        fs::create_dir_all(&requests).unwrap();
+        fs::create_dir_all(&app).unwrap();
+        fs::create_dir_all(sp.join("six-1.16.0.dist-info")).unwrap();
+        fs::write(
+            requests.join(DIST_INFO_METADATA),
+            "Metadata-Version: 2.1\nName: requests\nVersion: 2.32.3\nSummary: Python HTTP for Humans.\nRequires-Dist: charset-normalizer<4,>=2\nRequires-Dist: PySocks!=1.5.7,>=1.5.6; extra == \"socks\"\n",
+        )
+        .unwrap();
+        fs::write(
+            requests.join(RECORD_FILE),
+            "requests/__init__.py,sha256=abc,10\nrequests-2.32.3.dist-info/METADATA,,\n",
+        )
+        .unwrap();
+        fs::write(
+            app.join(DIST_INFO_METADATA),
+            "Name: my-app\nVersion: 0.1.0\n",
+        )
+        .unwrap();
+        fs::write(
+            app.join(DIRECT_URL_FILE),
+            r#"{"url": "file:///src/app", "dir_info": {"editable": true}}"#,
+        )
+        .unwrap();
+        let distributions = distributions(&prefix);
+        let files: Vec<Vec<PathBuf>> = distributions.iter().map(|d| d.files()).collect();
+        fs::remove_dir_all(&prefix).unwrap();
+
+        assert_eq!(
+            distributions
+                .iter()
+                .map(|d| d.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["my-app", "requests", "six"]
+        );
+        assert!(distributions[0].origin.is_editable());
+        let requests = &distributions[1];
+        assert_eq!(requests.summary.as_deref(), Some("Python HTTP for Humans."));
+        assert_eq!(requests.requires.len(), 2);
+        assert_eq!(requests.requires[1].key(), "pysocks");
+        assert_eq!(requests.origin, Origin::Index);
+        assert_eq!(
+            files[1],
+            vec![
+                sp.join("requests/__init__.py"),
+                sp.join("requests-2.32.3.dist-info")
+                    .join(DIST_INFO_METADATA)
+            ]
+        );
+        assert_eq!(distributions[2].version, "1.16.0");
+        assert!(files[2].is_empty());
+    }
+}
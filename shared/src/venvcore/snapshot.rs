@@ -0,0 +1,424 @@
+use super::{credentials, metadata::Metadata, venv::Venv};
+use crate::{
+    constants::{MAX_AUTO_SNAPSHOTS, SNAPSHOTS_DIR},
+    processes, settings, uv, validation,
+};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::{
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Snapshot {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub created: DateTime<Utc>,
+    #[serde(default)]
+    pub automatic: bool,
+    pub path: PathBuf,
+    pub python_version: String,
+    pub packages: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive: Option<PathBuf>,
+}
+
+impl Snapshot {
+    pub fn capture(venv: &Venv, label: Option<String>, automatic: bool) -> Self {
+        let created = Utc::now();
+        Snapshot {
+            id: created.format("%Y%m%d-%H%M%S").to_string(),
+            label: label.filter(|l| !l.trim().is_empty()),
+            created,
+            automatic,
+            path: venv.path.clone(),
+            python_version: venv
+                .config()
+                .and_then(|cfg| cfg.get("version_info").cloned())
+                .unwrap_or_else(|| venv.python_version.clone()),
+            packages: venv
+                .distributions()
+                .iter()
+                .map(|d| d.requirement())
+                .collect(),
+            archive: None,
+        }
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), String> {
+        fs::create_dir_all(dir).map_err(|e| format!("Error creating snapshot dir: {}", e))?;
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(dir.join(format!("{}.toml", self.id)), content)
+            .map_err(|e| format!("Error writing snapshot: {}", e))
+    }
+
+    pub fn matches(&self, query: &str) -> bool {
+        self.id == query || self.label.as_deref() == Some(query)
+    }
+}
+
+pub fn snapshots_dir(venv: &Venv) -> PathBuf {
+    venv.settings
+        .state_dir()
+        .join(SNAPSHOTS_DIR)
+        .join(&venv.name)
+}
+
+pub fn list(venv: &Venv) -> Vec<Snapshot> {
+    let Ok(entries) = fs::read_dir(snapshots_dir(venv)) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<Snapshot> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+        .filter_map(|p| fs::read_to_string(p).ok())
+        .filter_map(|content| toml::from_str(&content).ok())
+        .collect();
+    snapshots.sort_by(|a, b| a.created.cmp(&b.created).then(a.id.cmp(&b.id)));
+    snapshots
+}
+
+pub fn find(venv: &Venv, query: Option<&str>) -> Result<Snapshot, String> {
+    let snapshots = list(venv);
+    let found = match query {
+        Some(query) => snapshots.into_iter().rev().find(|s| s.matches(query)),
+        None => snapshots.into_iter().last(),
+    };
+    found.ok_or_else(|| match query {
+        Some(query) => format!("No snapshot '{}' found for {}", query, venv.name),
+        None => format!("No snapshots found for {}", venv.name),
+    })
+}
+
+pub fn create(venv: &Venv, label: Option<String>, archive: bool) -> Result<Snapshot, String> {
+    save_new(venv, Snapshot::capture(venv, label, false), archive)
+}
+
+pub fn auto(venv: &Venv, reason: &str) -> Result<Option<Snapshot>, String> {
+    auto_keeping(venv, reason, None)
+}
+
+fn auto_keeping(
+    venv: &Venv,
+    reason: &str,
+    keep: Option<&Snapshot>,
+) -> Result<Option<Snapshot>, String> {
+    let snapshot = Snapshot::capture(venv, Some(reason.to_string()), true);
+    if snapshot.packages.is_empty() {
+        return Ok(None);
+    }
+    let snapshot = save_new(venv, snapshot, false)?;
+    let automatic: Vec<Snapshot> = list(venv)
+        .into_iter()
+        .filter(|s| s.automatic && keep.is_none_or(|k| k.id != s.id))
+        .collect();
+    let excess = automatic.len().saturating_sub(MAX_AUTO_SNAPSHOTS);
+    for old in &automatic[..excess] {
+        remove(venv, old)?;
+    }
+    Ok(Some(snapshot))
+}
+
+fn save_new(venv: &Venv, mut snapshot: Snapshot, archive: bool) -> Result<Snapshot, String> {
+    let dir = snapshots_dir(venv);
+    let base = snapshot.id.clone();
+    let mut counter = 1;
+    while dir.join(format!("{}.toml", snapshot.id)).exists() {
+        counter += 1;
+        snapshot.id = format!("{}-{}", base, counter);
+    }
+    if archive {
+        fs::create_dir_all(&dir).map_err(|e| format!("Error creating snapshot dir: {}", e))?;
+        let path = dir.join(format!("{}.tar.gz", snapshot.id));
+        write_archive(&venv.path, &path)?;
+        snapshot.archive = Some(path);
+    }
+    snapshot.save(&dir)?;
+    Ok(snapshot)
+}
+
+pub fn remove(venv: &Venv, snapshot: &Snapshot) -> Result<(), String> {
+    let dir = snapshots_dir(venv);
+    if let Some(archive) = &snapshot.archive {
+        let _ = fs::remove_file(archive);
+    }
+    fs::remove_file(dir.join(format!("{}.toml", snapshot.id)))
+        .map_err(|e| format!("Error removing snapshot {}: {}", snapshot.id, e))
+}
+
+pub fn remove_all(venv: &Venv) -> Result<(), String> {
+    match fs::remove_dir_all(snapshots_dir(venv)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            Err(format!("Error removing snapshots: {}", e))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn rename_all(venv: &Venv, old_name: &str) -> Result<(), String> {
+    let old_dir = venv.settings.state_dir().join(SNAPSHOTS_DIR).join(old_name);
+    if !old_dir.exists() {
+        return Ok(());
+    }
+    fs::rename(&old_dir, snapshots_dir(venv)).map_err(|e| format!("Error moving snapshots: {}", e))
+}
+
+fn write_archive(source: &Path, target: &Path) -> Result<(), String> {
+    let file = fs::File::create(target)
+        .map_err(|e| format!("Error creating {}: {}", target.display(), e))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    builder.follow_symlinks(false);
+    let result = builder
+        .append_dir_all(".", source)
+        .and_then(|_| builder.into_inner())
+        .and_then(|encoder| encoder.finish());
+    if let Err(e) = result {
+        let _ = fs::remove_file(target);
+        return Err(format!("Error writing snapshot archive: {}", e));
+    }
+    Ok(())
+}
+
+fn restore_archive(venv: &Venv, snapshot: &Snapshot, archive: &Path) -> Result<(), String> {
+    validation::ensure_inside(&venv.settings.venvs_path, &venv.path)?;
+    let metadata = Metadata::load(&venv.path);
+    let backup = venv
+        .settings
+        .venvs_path
+        .join(format!(".{}.rollback-backup", venv.name));
+    fs::rename(&venv.path, &backup).map_err(|e| format!("Error creating backup: {}", e))?;
+    let unpacked = fs::File::open(archive)
+        .map_err(|e| format!("Error opening {}: {}", archive.display(), e))
+        .and_then(|file| {
+            tar::Archive::new(GzDecoder::new(file))
+                .unpack(&venv.path)
+                .map_err(|e| format!("Error unpacking snapshot archive: {}", e))
+        });
+    if let Err(e) = unpacked {
+        let _ = fs::remove_dir_all(&venv.path);
+        fs::rename(&backup, &venv.path).map_err(|e| format!("Error restoring backup: {}", e))?;
+        return Err(e);
+    }
+    let _ = fs::remove_dir_all(&backup);
+    metadata.save(&venv.path)?;
+    if snapshot.path != venv.path {
+        let old = snapshot.path.to_string_lossy().to_string();
+        let new = venv.path.to_string_lossy().to_string();
+        let old_name = snapshot
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        venv.rewrite_paths(&old, &new, &old_name)?;
+    }
+    Ok(())
+}
+
+pub fn sync_args(
+    venv: &Venv,
+    requirements: &Path,
+    index: &settings::IndexSettings,
+) -> Vec<OsString> {
+    let mut args = vec![
+        OsString::from("pip"),
+        OsString::from("sync"),
+        OsString::from("--python"),
+        venv.python().into_os_string(),
+        requirements.as_os_str().to_os_string(),
+    ];
+    args.extend(index.args().into_iter().map(OsString::from));
+    args
+}
+
+pub async fn rollback(venv: &Venv, snapshot: &Snapshot) -> Result<(), String> {
+    auto_keeping(
+        venv,
+        &format!("before rollback to {}", snapshot.id),
+        Some(snapshot),
+    )?;
+    match &snapshot.archive {
+        Some(archive) => restore_archive(venv, snapshot, archive)?,
+        None => restore_packages(venv, snapshot).await?,
+    }
+    if let Err(e) = venv.mark_used() {
+        eprintln!("{}", e.yellow());
+    }
+    Ok(())
+}
+
+async fn restore_packages(venv: &Venv, snapshot: &Snapshot) -> Result<(), String> {
+    let index = credentials::with_credentials(&venv.index()?);
+    let requirements = std::env::temp_dir().join(format!(
+        "pypilot-rollback-{}-{}.txt",
+        venv.name,
+        std::process::id()
+    ));
+    let mut content = snapshot.packages.join("\n");
+    content.push('\n');
+    fs::write(&requirements, content)
+        .map_err(|e| format!("Error writing {}: {}", requirements.display(), e))?;
+    let args = sync_args(venv, &requirements, &index);
+    let mut child = processes::create_child_cmd_with_env(&uv::uv_cmd(), &args, "", &index.env());
+    let result = processes::run_command(&mut child)
+        .await
+        .map_err(|_| "Error restoring packages".to_string());
+    let _ = fs::remove_file(&requirements);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{doctor, venvcore::testutil};
+
+    fn fake_venv(name: &str) -> Venv {
+        let mut venv = testutil::fake_venv("snapshot", name);
+        venv.python_version = "3.11.4".to_string();
+        testutil::fake_dist_infos(&venv, &["requests-2.31.0", "six-1.16.0"]);
+        venv
+    }
+
+    fn cleanup(venv: &Venv) {
+        let _ = fs::remove_dir_all(&venv.path);
+        let _ = remove_all(venv);
+    }
+
+    #[test]
+    fn test_create_and_find() {
+        let venv = fake_venv("create_find");
+        let first = create(&venv, Some("stable".to_string()), false).unwrap();
+        let second = create(&venv, None, false).unwrap();
+        let snapshots = list(&venv);
+        let by_label = find(&venv, Some("stable"));
+        let latest = find(&venv, None);
+        let missing = find(&venv, Some("nope"));
+        cleanup(&venv);
+        assert_eq!(snapshots.len(), 2);
+        assert_ne!(first.id, second.id);
+        assert_eq!(first.packages, vec!["requests==2.31.0", "six==1.16.0"]);
+        assert_eq!(by_label.unwrap(), first);
+        assert_eq!(latest.unwrap(), second);
+        assert!(missing.unwrap_err().contains("No snapshot 'nope'"));
+    }
+
+    #[test]
+    fn test_auto_keeps_latest() {
+        let venv = fake_venv("auto");
+        let manual = create(&venv, Some("manual".to_string()), false).unwrap();
+        for i in 0..MAX_AUTO_SNAPSHOTS + 2 {
+            auto(&venv, &format!("before installing pkg{}", i)).unwrap();
+        }
+        let snapshots = list(&venv);
+        cleanup(&venv);
+        assert_eq!(snapshots.len(), MAX_AUTO_SNAPSHOTS + 1);
+        assert!(snapshots.contains(&manual));
+        assert_eq!(
+            snapshots.last().unwrap().label.as_deref(),
+            Some(format!("before installing pkg{}", MAX_AUTO_SNAPSHOTS + 1).as_str())
+        );
+    }
+
+    #[test]
+    fn test_auto_keeps_rollback_target() {
+        let venv = fake_venv("auto_target");
+        let target = auto(&venv, "before installing pkg").unwrap().unwrap();
+        for i in 0..MAX_AUTO_SNAPSHOTS {
+            auto_keeping(&venv, &format!("before rollback {}", i), Some(&target)).unwrap();
+        }
+        let snapshots = list(&venv);
+        cleanup(&venv);
+        assert!(snapshots.contains(&target));
+    }
+
+    #[test]
+    fn test_capture_origins() {
+        let venv = fake_venv("origins");
+        let sp = testutil::site_packages(&venv);
+        let app = sp.join("my_app-0.1.0.dist-info");
+        let lib = sp.join("lib-1.0.dist-info");
+        fs::create_dir_all(&app).unwrap();
+        fs::create_dir_all(&lib).unwrap();
+        fs::write(
+            app.join("direct_url.json"),
+            r#"{"url": "file:///src/app", "dir_info": {"editable": true}}"#,
+        )
+        .unwrap();
+        fs::write(
+            lib.join("direct_url.json"),
+            r#"{"url": "https://github.com/a/lib.git", "vcs_info": {"vcs": "git", "commit_id": "abc"}}"#,
+        )
+        .unwrap();
+        let snapshot = Snapshot::capture(&venv, None, false);
+        cleanup(&venv);
+        assert_eq!(
+            snapshot.packages,
+            vec![
+                "lib @ git+https://github.com/a/lib.git@abc",
+                "-e file:///src/app",
+                "requests==2.31.0",
+                "six==1.16.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_auto_skips_empty() {
+        let mut venv = fake_venv("auto_empty");
+        venv.path = venv.path.join("missing");
+        let snapshot = auto(&venv, "before installing").unwrap();
+        venv.path.pop();
+        cleanup(&venv);
+        assert_eq!(snapshot, None);
+    }
+
+    #[test]
+    fn test_rename_all() {
+        let mut venv = fake_venv("rename_old");
+        create(&venv, None, false).unwrap();
+        venv.name = "rename_new".to_string();
+        rename_all(&venv, "rename_old").unwrap();
+        let snapshots = list(&venv);
+        cleanup(&venv);
+        assert_eq!(snapshots.len(), 1);
+    }
+
+    #[test]
+    fn test_sync_args() {
+        let venv = fake_venv("sync_args");
+        let index = settings::IndexSettings {
+            no_index: true,
+            ..Default::default()
+        };
+        let args = sync_args(&venv, Path::new("/tmp/req.txt"), &index);
+        cleanup(&venv);
+        assert_eq!(args[..2], [OsString::from("pip"), OsString::from("sync")]);
+        assert_eq!(args[4], OsString::from("/tmp/req.txt"));
+        assert_eq!(args[5], OsString::from("--no-index"));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_archive() {
+        let venv = fake_venv("archive");
+        fs::write(venv.path.join("pyvenv.cfg"), "version_info = 3.11.4\n").unwrap();
+        let snapshot = create(&venv, None, true).unwrap();
+        assert!(snapshot.archive.as_ref().unwrap().is_file());
+        let sp = testutil::site_packages(&venv);
+        fs::remove_dir_all(sp.join("six-1.16.0.dist-info")).unwrap();
+        fs::create_dir_all(sp.join("six-1.17.0.dist-info")).unwrap();
+        let result = rollback(&venv, &snapshot).await;
+        let packages = doctor::recorded_packages(&venv);
+        let snapshots = list(&venv);
+        let metadata = Metadata::load(&venv.path);
+        cleanup(&venv);
+        result.unwrap();
+        assert!(metadata.last_used.is_some());
+        assert_eq!(packages, vec!["requests==2.31.0", "six==1.16.0"]);
+        assert_eq!(snapshots.len(), 2);
+        assert!(snapshots[1].packages.contains(&"six==1.17.0".to_string()));
+    }
+}
@@ -0,0 +1,29 @@
+use super::venv::Venv;
+use std::{fs, path::PathBuf};
+
+pub fn fake_venv(prefix: &str, name: &str) -> Venv {
+    let root = std::env::temp_dir().join(format!("pypilot_{}_{}", prefix, std::process::id()));
+    let mut venv = Venv::new(name.to_string(), "".to_string(), vec![], false);
+    venv.settings.venvs_path = root.clone();
+    venv.path = root.join(name);
+    fs::create_dir_all(&venv.path).unwrap();
+    venv
+}
+
+pub fn site_packages(venv: &Venv) -> PathBuf {
+    let dir = if cfg!(target_os = "windows") {
+        venv.path.join("Lib/site-packages")
+    } else {
+        venv.path.join("lib/python3.11/site-packages")
+    };
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+pub fn fake_dist_infos(venv: &Venv, stems: &[&str]) -> PathBuf {
+    let sp = site_packages(venv);
+    for stem in stems {
+        fs::create_dir_all(sp.join(format!("{}.dist-info", stem))).unwrap();
+    }
+    sp
+}
@@ -0,0 +1,543 @@
+use super::site_packages::InstalledDistribution;
+use crate::{marker::MarkerEnv, requirement::normalize_name, version::Version};
+use colored::Colorize;
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    Conflict,
+    Missing,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependency {
+    pub key: String,
+    pub name: String,
+    pub required: String,
+    pub status: Status,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    pub dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    pub packages: BTreeMap<String, Package>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeFormat {
+    Text,
+    Json,
+    Dot,
+}
+
+impl FromStr for TreeFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "text" => Ok(TreeFormat::Text),
+            "json" => Ok(TreeFormat::Json),
+            "dot" => Ok(TreeFormat::Dot),
+            other => Err(format!("Unknown format '{}', use text, json or dot", other)),
+        }
+    }
+}
+
+struct Edge {
+    key: String,
+    name: String,
+    required: String,
+    status: Status,
+}
+
+impl Graph {
+    pub fn new(distributions: &[InstalledDistribution], env: &MarkerEnv) -> Self {
+        let installed: BTreeMap<String, &InstalledDistribution> =
+            distributions.iter().map(|d| (d.key(), d)).collect();
+        let packages = installed
+            .iter()
+            .map(|(key, dist)| {
+                let mut dependencies: Vec<Dependency> = dist
+                    .requires
+                    .iter()
+                    .filter_map(|r| {
+                        let applies = r.marker.as_deref().map(|m| env.evaluate(m));
+                        if applies == Some(Some(false)) {
+                            return None;
+                        }
+                        let key = r.key();
+                        let dependency = installed.get(&key);
+                        let status = match dependency {
+                            None if applies == Some(None) => return None,
+                            None => Status::Missing,
+                            Some(dep) => match dep.version.parse::<Version>() {
+                                Ok(v) if !r.spec.contains(&v) => Status::Conflict,
+                                _ => Status::Ok,
+                            },
+                        };
+                        Some(Dependency {
+                            name: dependency.map_or(r.name.clone(), |d| d.name.clone()),
+                            required: if r.spec.is_empty() {
+                                "any".to_string()
+                            } else {
+                                r.spec.to_string()
+                            },
+                            key,
+                            status,
+                        })
+                    })
+                    .collect();
+                dependencies.sort_by(|a, b| a.key.cmp(&b.key));
+                dependencies.dedup_by(|a, b| a.key == b.key);
+                let package = Package {
+                    name: dist.name.clone(),
+                    version: dist.version.clone(),
+                    dependencies,
+                };
+                (key.clone(), package)
+            })
+            .collect();
+        Graph { packages }
+    }
+
+    pub fn find(&self, name: &str) -> Option<String> {
+        let key = normalize_name(name);
+        self.packages.contains_key(&key).then_some(key)
+    }
+
+    pub fn roots(&self) -> Vec<String> {
+        let required: BTreeSet<&String> = self
+            .packages
+            .values()
+            .flat_map(|p| p.dependencies.iter().map(|d| &d.key))
+            .collect();
+        let mut roots: Vec<String> = self
+            .packages
+            .keys()
+            .filter(|key| !required.contains(key))
+            .cloned()
+            .collect();
+        let mut reached = self.reachable(&roots, false);
+        for key in self.packages.keys() {
+            if !reached.contains(key) {
+                roots.push(key.clone());
+                reached.extend(self.reachable(std::slice::from_ref(key), false));
+            }
+        }
+        roots
+    }
+
+    pub fn dependents(&self, key: &str) -> Vec<(&String, &Dependency)> {
+        self.packages
+            .iter()
+            .flat_map(|(parent, package)| {
+                package
+                    .dependencies
+                    .iter()
+                    .filter(|d| d.key == key)
+                    .map(move |d| (parent, d))
+            })
+            .collect()
+    }
+
+    pub fn conflicts(&self, start: &[String], reverse: bool) -> Vec<(&Package, &Dependency)> {
+        let reached = self.reachable(start, reverse);
+        self.packages
+            .iter()
+            .filter(|(key, _)| reached.contains(*key))
+            .flat_map(|(_, p)| {
+                p.dependencies
+                    .iter()
+                    .filter(|d| d.status != Status::Ok)
+                    .filter(|d| !reverse || reached.contains(&d.key))
+                    .map(move |d| (p, d))
+            })
+            .collect()
+    }
+
+    fn label(&self, key: &str) -> String {
+        match self.packages.get(key) {
+            Some(package) => format!("{}=={}", package.name, package.version),
+            None => key.to_string(),
+        }
+    }
+
+    fn edges(&self, key: &str, reverse: bool) -> Vec<Edge> {
+        if reverse {
+            self.dependents(key)
+                .into_iter()
+                .map(|(parent, dep)| Edge {
+                    key: parent.clone(),
+                    name: self.packages[parent].name.clone(),
+                    required: dep.required.clone(),
+                    status: dep.status,
+                })
+                .collect()
+        } else {
+            self.packages.get(key).map_or(Vec::new(), |package| {
+                package
+                    .dependencies
+                    .iter()
+                    .map(|dep| Edge {
+                        key: dep.key.clone(),
+                        name: dep.name.clone(),
+                        required: dep.required.clone(),
+                        status: dep.status,
+                    })
+                    .collect()
+            })
+        }
+    }
+
+    fn reachable(&self, start: &[String], reverse: bool) -> BTreeSet<String> {
+        let mut seen = BTreeSet::new();
+        let mut pending = start.to_vec();
+        while let Some(key) = pending.pop() {
+            if seen.insert(key.clone()) {
+                pending.extend(self.edges(&key, reverse).into_iter().map(|e| e.key));
+            }
+        }
+        seen
+    }
+}
+
+fn write_text(
+    graph: &Graph,
+    key: &str,
+    prefix: &str,
+    reverse: bool,
+    path: &mut Vec<String>,
+    out: &mut String,
+) {
+    let edges = graph.edges(key, reverse);
+    for (i, edge) in edges.iter().enumerate() {
+        let last = i + 1 == edges.len();
+        let cycle = path.contains(&edge.key);
+        let label = if graph.packages.contains_key(&edge.key) {
+            graph.label(&edge.key)
+        } else {
+            edge.name.clone()
+        };
+        let status = match edge.status {
+            Status::Ok => "",
+            Status::Conflict => ", conflict",
+            Status::Missing => ", missing",
+        };
+        let note = if reverse { "requires " } else { "required: " };
+        let mut line = format!("{} [{}{}{}]", label, note, edge.required, status);
+        if cycle {
+            line.push_str(" (cycle)");
+        }
+        if edge.status != Status::Ok {
+            line = line.red().to_string();
+        }
+        out.push_str(&format!(
+            "{}{}{}\n",
+            prefix,
+            if last { "└── " } else { "├── " },
+            line
+        ));
+        if !cycle && graph.packages.contains_key(&edge.key) {
+            path.push(edge.key.clone());
+            let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+            write_text(graph, &edge.key, &prefix, reverse, path, out);
+            path.pop();
+        }
+    }
+}
+
+pub fn to_text(graph: &Graph, start: &[String], reverse: bool) -> String {
+    let mut out = String::new();
+    for key in start {
+        out.push_str(&format!("{}\n", graph.label(key)));
+        write_text(graph, key, "", reverse, &mut vec![key.clone()], &mut out);
+    }
+    out
+}
+
+fn json_children(graph: &Graph, key: &str, reverse: bool, path: &mut Vec<String>) -> Vec<Value> {
+    let children = if reverse {
+        "required_by"
+    } else {
+        "dependencies"
+    };
+    graph
+        .edges(key, reverse)
+        .into_iter()
+        .map(|edge| {
+            let cycle = path.contains(&edge.key);
+            let nested = if cycle {
+                Vec::new()
+            } else {
+                path.push(edge.key.clone());
+                let nested = json_children(graph, &edge.key, reverse, path);
+                path.pop();
+                nested
+            };
+            json!({
+                "name": edge.name,
+                "version": graph.packages.get(&edge.key).map(|p| p.version.clone()),
+                "required": edge.required,
+                "status": edge.status,
+                children: nested,
+            })
+        })
+        .collect()
+}
+
+pub fn to_json(graph: &Graph, start: &[String], reverse: bool) -> Result<String, String> {
+    let children = if reverse {
+        "required_by"
+    } else {
+        "dependencies"
+    };
+    let nodes: Vec<Value> = start
+        .iter()
+        .filter_map(|key| graph.packages.get(key).map(|p| (key, p)))
+        .map(|(key, package)| {
+            json!({
+                "name": package.name,
+                "version": package.version,
+                children: json_children(graph, key, reverse, &mut vec![key.clone()]),
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&nodes).map_err(|e| e.to_string())
+}
+
+fn dot_id(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\\\""))
+}
+
+pub fn to_dot(graph: &Graph, start: &[String], reverse: bool) -> String {
+    let keys = graph.reachable(start, reverse);
+    let mut out = String::from("digraph dependencies {\n    node [shape=box];\n");
+    for key in &keys {
+        match graph.packages.get(key) {
+            Some(package) => out.push_str(&format!(
+                "    {} [label={}];\n",
+                dot_id(key),
+                dot_id(&format!("{}\\n{}", package.name, package.version))
+            )),
+            None => out.push_str(&format!(
+                "    {} [label={}, color=red, style=dashed];\n",
+                dot_id(key),
+                dot_id(&format!("{}\\nmissing", key))
+            )),
+        }
+    }
+    for (key, package) in keys
+        .iter()
+        .filter_map(|key| graph.packages.get(key).map(|p| (key, p)))
+    {
+        for dep in package
+            .dependencies
+            .iter()
+            .filter(|d| keys.contains(&d.key))
+        {
+            let color = if dep.status == Status::Ok {
+                ""
+            } else {
+                ", color=red"
+            };
+            out.push_str(&format!(
+                "    {} -> {} [label={}{}];\n",
+                dot_id(key),
+                dot_id(&dep.key),
+                dot_id(&dep.required),
+                color
+            ));
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::site_packages::Origin;
+    use std::path::PathBuf;
+
+    fn dist(name: &str, version: &str, requires: &[&str]) -> InstalledDistribution {
+        InstalledDistribution {
+            name: name.to_string(),
+            version: version.to_string(),
+            summary: None,
+            requires: requires.iter().map(|r| r.parse().unwrap()).collect(),
+            origin: Origin::Index,
+            dist_info: PathBuf::from(format!("{}-{}.dist-info", name, version)),
+        }
+    }
+
+    fn graph() -> Graph {
+        let mut env = MarkerEnv::new(Some("3.11.4"), None);
+        env.set("sys_platform", "linux");
+        Graph::new(
+            &[
+                dist(
+                    "requests",
+                    "2.32.3",
+                    &[
+                        "idna<4,>=2.5",
+                        "urllib3<3,>=1.21.1",
+                        "PySocks>=1.5.6; extra == \"socks\"",
+                        "colorama; sys_platform == \"win32\"",
+                    ],
+                ),
+                dist(
+                    "my_app",
+                    "0.1.0",
+                    &[
+                        "requests>=2.0",
+                        "missing-dep",
+                        "typing-extensions>=4; python_version >= \"3.8\"",
+                        "tomli>=1.1; python_version < \"3.11\"",
+                        "zipp; platform_release >= \"5\"",
+                    ],
+                ),
+                dist("idna", "3.7", &[]),
+                dist("urllib3", "3.0.0", &[]),
+                dist("a", "1.0", &["b"]),
+                dist("b", "1.0", &["a"]),
+            ],
+            &env,
+        )
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!("DOT".parse::<TreeFormat>(), Ok(TreeFormat::Dot));
+        assert_eq!("text".parse::<TreeFormat>(), Ok(TreeFormat::Text));
+        assert!("svg".parse::<TreeFormat>().is_err());
+    }
+
+    #[test]
+    fn test_graph() {
+        let graph = graph();
+        let requests = &graph.packages["requests"];
+        assert_eq!(
+            requests
+                .dependencies
+                .iter()
+                .map(|d| (d.key.as_str(), d.status))
+                .collect::<Vec<_>>(),
+            vec![("idna", Status::Ok), ("urllib3", Status::Conflict)]
+        );
+        assert_eq!(graph.roots(), vec!["my-app", "a"]);
+        assert_eq!(graph.find("My_App"), Some("my-app".to_string()));
+        assert_eq!(graph.find("numpy"), None);
+        assert_eq!(graph.dependents("requests").len(), 1);
+
+        assert_eq!(
+            graph.packages["my-app"]
+                .dependencies
+                .iter()
+                .map(|d| (d.key.as_str(), d.status))
+                .collect::<Vec<_>>(),
+            vec![
+                ("missing-dep", Status::Missing),
+                ("requests", Status::Ok),
+                ("typing-extensions", Status::Missing)
+            ]
+        );
+
+        let conflicts = |start: &str, reverse: bool| -> Vec<(String, String)> {
+            graph
+                .conflicts(&[start.to_string()], reverse)
+                .iter()
+                .map(|(p, d)| (p.name.clone(), d.name.clone()))
+                .collect()
+        };
+        let pair = |p: &str, d: &str| (p.to_string(), d.to_string());
+        assert_eq!(
+            conflicts("my-app", false),
+            vec![
+                pair("my_app", "missing-dep"),
+                pair("my_app", "typing-extensions"),
+                pair("requests", "urllib3")
+            ]
+        );
+        assert_eq!(
+            conflicts("requests", false),
+            vec![pair("requests", "urllib3")]
+        );
+        assert!(conflicts("idna", true).is_empty());
+        assert!(conflicts("a", false).is_empty());
+    }
+
+    #[test]
+    fn test_to_text() {
+        colored::control::set_override(false);
+        let graph = graph();
+        let text = to_text(&graph, &["my-app".to_string()], false);
+        assert_eq!(
+            text,
+            "my_app==0.1.0\n\
+             ├── missing-dep [required: any, missing]\n\
+             ├── requests==2.32.3 [required: >=2.0]\n\
+             │   ├── idna==3.7 [required: <4,>=2.5]\n\
+             │   └── urllib3==3.0.0 [required: <3,>=1.21.1, conflict]\n\
+             └── typing-extensions [required: >=4, missing]\n"
+        );
+
+        let reverse = to_text(&graph, &["idna".to_string()], true);
+        assert_eq!(
+            reverse,
+            "idna==3.7\n\
+             └── requests==2.32.3 [requires <4,>=2.5]\n\
+             \x20   └── my_app==0.1.0 [requires >=2.0]\n"
+        );
+
+        let cycle = to_text(&graph, &["a".to_string()], false);
+        assert!(cycle.ends_with("    └── a==1.0 [required: any] (cycle)\n"));
+    }
+
+    #[test]
+    fn test_to_json() {
+        let graph = graph();
+        let json = to_json(&graph, &["requests".to_string()], false).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0]["name"], "requests");
+        assert_eq!(value[0]["dependencies"][1]["name"], "urllib3");
+        assert_eq!(value[0]["dependencies"][1]["status"], "conflict");
+        assert_eq!(value[0]["dependencies"][1]["required"], "<3,>=1.21.1");
+
+        let json = to_json(&graph, &["urllib3".to_string()], true).unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0]["required_by"][0]["name"], "requests");
+        assert_eq!(
+            value[0]["required_by"][0]["required_by"][0]["name"],
+            "my_app"
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let graph = graph();
+        let dot = to_dot(&graph, &["my-app".to_string()], false);
+        assert!(dot.starts_with("digraph dependencies {"));
+        assert!(dot.contains("\"requests\" [label=\"requests\\n2.32.3\"];"));
+        assert!(dot.contains("\"requests\" -> \"urllib3\" [label=\"<3,>=1.21.1\", color=red];"));
+        assert!(dot.contains(
+            "\"missing-dep\" [label=\"missing-dep\\nmissing\", color=red, style=dashed];"
+        ));
+        assert!(!dot.contains("\"a\""));
+
+        let reverse = to_dot(&graph, &["idna".to_string()], true);
+        assert!(reverse.contains("\"my-app\" -> \"requests\""));
+        assert!(!reverse.contains("urllib3"));
+    }
+}
@@ -0,0 +1,182 @@
+use super::venv::Venv;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvUsage {
+    pub name: String,
+    pub total: u64,
+    pub unique: u64,
+    pub shared: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageReport {
+    pub envs: Vec<EnvUsage>,
+    pub apparent: u64,
+    pub actual: u64,
+}
+
+struct FileEntry {
+    id: Option<(u64, u64)>,
+    links: u64,
+    len: u64,
+}
+
+pub fn disk_usage(venvs: &[Venv]) -> UsageReport {
+    let files: Vec<Vec<FileEntry>> = venvs
+        .iter()
+        .map(|v| {
+            let mut entries = Vec::new();
+            walk(&v.path, &mut entries);
+            entries
+        })
+        .collect();
+
+    let mut seen: HashMap<(u64, u64), (u64, usize)> = HashMap::new();
+    for (env, entries) in files.iter().enumerate() {
+        for id in entries.iter().filter_map(|f| f.id) {
+            let entry = seen.entry(id).or_insert((0, env));
+            entry.0 += 1;
+            if entry.1 != env {
+                entry.1 = usize::MAX;
+            }
+        }
+    }
+
+    let mut envs = Vec::with_capacity(venvs.len());
+    let mut counted: HashSet<(u64, u64)> = HashSet::new();
+    let mut apparent = 0;
+    let mut actual = 0;
+    for (env, (venv, entries)) in venvs.iter().zip(&files).enumerate() {
+        let mut usage = EnvUsage {
+            name: venv.name.clone(),
+            total: 0,
+            unique: 0,
+            shared: 0,
+        };
+        let mut counted_in_env: HashSet<(u64, u64)> = HashSet::new();
+        for file in entries {
+            usage.total += file.len;
+            let Some(id) = file.id else {
+                usage.unique += file.len;
+                actual += file.len;
+                continue;
+            };
+            if !counted_in_env.insert(id) {
+                continue;
+            }
+            if counted.insert(id) {
+                actual += file.len;
+            }
+            let (links_in_root, owner) = seen[&id];
+            if file.links <= links_in_root && owner == env {
+                usage.unique += file.len;
+            } else {
+                usage.shared += file.len;
+            }
+        }
+        apparent += usage.total;
+        envs.push(usage);
+    }
+    UsageReport {
+        envs,
+        apparent,
+        actual,
+    }
+}
+
+fn walk(path: &Path, entries: &mut Vec<FileEntry>) {
+    let Ok(dir) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in dir.filter_map(Result::ok) {
+        let Ok(meta) = entry.path().symlink_metadata() else {
+            continue;
+        };
+        if meta.is_dir() {
+            walk(&entry.path(), entries);
+        } else if meta.is_file() {
+            let (id, links) = file_id(&meta);
+            entries.push(FileEntry {
+                id,
+                links,
+                len: meta.len(),
+            });
+        }
+    }
+}
+
+#[cfg(unix)]
+fn file_id(meta: &fs::Metadata) -> (Option<(u64, u64)>, u64) {
+    use std::os::unix::fs::MetadataExt;
+    (Some((meta.dev(), meta.ino())), meta.nlink())
+}
+
+#[cfg(not(unix))]
+fn file_id(_meta: &fs::Metadata) -> (Option<(u64, u64)>, u64) {
+    (None, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_disk_usage_empty() {
+        let report = disk_usage(&[Venv::from_path(PathBuf::from("/pypilot/missing"))]);
+        assert_eq!(report.envs[0].total, 0);
+        assert_eq!(report.apparent, 0);
+        assert_eq!(report.actual, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_disk_usage_hardlinks() {
+        let base = std::env::temp_dir().join(format!("pypilot_usage_{}", std::process::id()));
+        let cache = base.join("cache");
+        let root = base.join("venvs");
+        for dir in [&cache, &root.join("a"), &root.join("b")] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(cache.join("wheel"), [0u8; 1000]).unwrap();
+        fs::hard_link(cache.join("wheel"), root.join("a/from_cache")).unwrap();
+        fs::write(root.join("a/own"), [0u8; 100]).unwrap();
+        fs::hard_link(root.join("a/own"), root.join("a/own_link")).unwrap();
+        fs::write(root.join("a/between"), [0u8; 10]).unwrap();
+        fs::hard_link(root.join("a/between"), root.join("b/between")).unwrap();
+        fs::write(root.join("b/own"), [0u8; 200]).unwrap();
+
+        let venvs = [
+            Venv::from_path(root.join("a")),
+            Venv::from_path(root.join("b")),
+        ];
+        let report = disk_usage(&venvs);
+        fs::remove_dir_all(&base).unwrap();
+
+        assert_eq!(
+            report.envs[0],
+            EnvUsage {
+                name: "a".to_string(),
+                total: 1210,
+                unique: 100,
+                shared: 1010,
+            }
+        );
+        assert_eq!(
+            report.envs[1],
+            EnvUsage {
+                name: "b".to_string(),
+                total: 210,
+                unique: 200,
+                shared: 10,
+            }
+        );
+        assert_eq!(report.apparent, 1420);
+        assert_eq!(report.actual, 1310);
+    }
+}
@@ -1,22 +1,42 @@
 use crate::{
     core::processes,
+    settings::Settings,
     utility::constants::{
-        BASH_CMD, UV_UNIX_INSTALL_ARGS, UV_UNIX_UNINSTALL_ARGS, UV_WINGET_INSTALL_ARGS,
-        UV_WINGET_UNINSTALL_ARGS, WINGET_CMD,
+        BASH_CMD, UV_MIN_VERSION, UV_RECEIPT_FILE, UV_UNIX_INSTALL_ARGS, UV_UNIX_INSTALL_URL,
+        UV_WINGET_INSTALL_ARGS, UV_WINGET_UNINSTALL_ARGS, WINGET_CMD,
     },
     utils::confirm,
+    uvarchive::{self, InstallRecord},
+    version::Version,
 };
 use colored::Colorize;
+use std::path::{Path, PathBuf};
 
-pub async fn install<R: std::io::Read>(input: R) -> Result<(), String> {
+pub fn install_args(version: Option<&Version>) -> (&'static str, Vec<String>) {
+    let owned = |args: &[&str]| args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+    match (cfg!(target_os = "windows"), version) {
+        (true, None) => (WINGET_CMD, owned(UV_WINGET_INSTALL_ARGS)),
+        (true, Some(v)) => {
+            let mut args = owned(UV_WINGET_INSTALL_ARGS);
+            args.extend(["--version".to_string(), v.to_string()]);
+            (WINGET_CMD, args)
+        }
+        (false, None) => (BASH_CMD, owned(UV_UNIX_INSTALL_ARGS)),
+        (false, Some(v)) => (
+            BASH_CMD,
+            vec![
+                "-c".to_string(),
+                format!("curl -LsSf {}/{}/install.sh | sh", UV_UNIX_INSTALL_URL, v),
+            ],
+        ),
+    }
+}
+
+pub async fn install<R: std::io::Read>(input: R, version: Option<&Version>) -> Result<(), String> {
     println!("{}", "Installing Astral UV...".yellow());
     println!("{}", "This will run the following command:".yellow());
 
-    let (cmd, args): (&str, &[&str]) = if cfg!(target_os = "windows") {
-        (WINGET_CMD, UV_WINGET_INSTALL_ARGS)
-    } else {
-        (BASH_CMD, UV_UNIX_INSTALL_ARGS)
-    };
+    let (cmd, args) = install_args(version);
 
     println!("{}", format!("  {} {}", cmd, args.join(" ")).red());
 
@@ -25,39 +45,381 @@ pub async fn install<R: std::io::Read>(input: R) -> Result<(), String> {
         return Ok(());
     }
 
-    let mut child = processes::create_child_cmd(cmd, args, "");
+    let mut child = processes::create_child_cmd(cmd, &args, "");
     processes::run_command(&mut child)
         .await
         .map_err(|_| "Installation failed".to_string())?;
     Ok(())
 }
 
-pub async fn uninstall<R: std::io::Read>(input: R) -> Result<(), String> {
-    println!("{}", "Uninstalling Astral UV...".yellow());
-    println!("{}", "This will run the following command:".yellow());
+pub async fn install_from_archive<R: std::io::Read>(
+    input: R,
+    source: &Path,
+    sha256: Option<&str>,
+    bin_dir: &Path,
+) -> Result<(), String> {
+    println!(
+        "{}",
+        "Installing Astral UV from a local archive...".yellow()
+    );
+    println!(
+        "{}",
+        format!("  {} -> {}", source.display(), bin_dir.display()).red()
+    );
+
+    if !confirm(input) {
+        println!("{}", "Exiting...".yellow());
+        return Ok(());
+    }
+
+    let record = uvarchive::install_from(source, sha256, bin_dir)?;
+    record.save(&Settings::get_settings().state_dir())?;
+    println!(
+        "{}",
+        format!(
+            "Astral UV installed to {} (sha256 {})",
+            bin_dir.display(),
+            record.sha256
+        )
+        .green()
+    );
+    let uv = bin_dir.join(uvarchive::UV_BINARIES[0]);
+    if which::which(uv_cmd()).ok() != Some(uv) {
+        println!(
+            "{}",
+            format!("Add {} to PATH to use this uv", bin_dir.display()).yellow()
+        );
+    }
+    Ok(())
+}
+
+pub async fn self_update(version: Option<&Version>) -> Result<(), String> {
+    let mut args = vec!["self".to_string(), "update".to_string()];
+    if let Some(v) = version {
+        args.push(v.to_string());
+    }
+    println!(
+        "{}",
+        format!("Running {} {}", uv_cmd(), args.join(" ")).yellow()
+    );
+    let mut child = processes::create_child_cmd(&uv_cmd(), &args, "");
+    processes::run_command(&mut child)
+        .await
+        .map_err(|_| "Self update failed".to_string())?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstallMethod {
+    Archive(InstallRecord),
+    Standalone(PathBuf),
+    Cargo,
+    Pipx,
+    Homebrew,
+    Winget,
+    Scoop,
+    Pip(PathBuf),
+    PackageManager,
+    Unknown(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UninstallAction {
+    Run(String, Vec<String>),
+    Remove(Vec<PathBuf>),
+    Manual(String),
+}
+
+impl InstallMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            InstallMethod::Archive(_) => "pypilot archive install",
+            InstallMethod::Standalone(_) => "standalone installer",
+            InstallMethod::Cargo => "cargo",
+            InstallMethod::Pipx => "pipx",
+            InstallMethod::Homebrew => "Homebrew",
+            InstallMethod::Winget => "winget",
+            InstallMethod::Scoop => "Scoop",
+            InstallMethod::Pip(_) => "pip",
+            InstallMethod::PackageManager => "system package manager",
+            InstallMethod::Unknown(_) => "unknown method",
+        }
+    }
 
-    let (cmd, args): (&str, &[&str]) = if cfg!(target_os = "windows") {
-        (WINGET_CMD, UV_WINGET_UNINSTALL_ARGS)
+    pub fn action(&self) -> UninstallAction {
+        let run = |cmd: &str, args: &[&str]| {
+            UninstallAction::Run(
+                cmd.to_string(),
+                args.iter().map(|a| a.to_string()).collect(),
+            )
+        };
+        match self {
+            InstallMethod::Archive(record) => UninstallAction::Remove(record.binaries.clone()),
+            InstallMethod::Standalone(bin_dir) => {
+                let mut files: Vec<PathBuf> = uvarchive::UV_BINARIES
+                    .iter()
+                    .map(|b| bin_dir.join(b))
+                    .filter(|p| p.exists())
+                    .collect();
+                files.push(receipt_path());
+                UninstallAction::Remove(files)
+            }
+            InstallMethod::Cargo => run("cargo", &["uninstall", "uv"]),
+            InstallMethod::Pipx => run("pipx", &["uninstall", "uv"]),
+            InstallMethod::Homebrew => run("brew", &["uninstall", "uv"]),
+            InstallMethod::Winget => run(WINGET_CMD, UV_WINGET_UNINSTALL_ARGS),
+            InstallMethod::Scoop => run("scoop", &["uninstall", "uv"]),
+            InstallMethod::Pip(python) => UninstallAction::Manual(format!(
+                "{} -m pip uninstall uv",
+                python.display()
+            )),
+            InstallMethod::PackageManager => UninstallAction::Manual(
+                "Use your system package manager, e.g. 'sudo apt remove uv', 'sudo dnf remove uv' or 'sudo pacman -R uv'"
+                    .to_string(),
+            ),
+            InstallMethod::Unknown(path) => {
+                UninstallAction::Manual(format!("Remove {} manually", path.display()))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for InstallMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+pub fn detect_method(
+    path: &Path,
+    resolved: &Path,
+    home: &Path,
+    receipt_dir: Option<&Path>,
+) -> InstallMethod {
+    let text = resolved.to_string_lossy().replace('\\', "/").to_lowercase();
+    let bin_dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+    let cargo_bin = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".cargo"))
+        .join("bin");
+    let python = ["python", "python3", "python.exe"]
+        .iter()
+        .map(|p| bin_dir.join(p))
+        .find(|p| p.exists());
+
+    if text.contains("/pipx/venvs/uv/") {
+        InstallMethod::Pipx
+    } else if text.contains("/cellar/uv/")
+        || text.contains("/homebrew/")
+        || text.contains("/linuxbrew/")
+    {
+        InstallMethod::Homebrew
+    } else if text.contains("/microsoft/winget/") {
+        InstallMethod::Winget
+    } else if text.contains("/scoop/") {
+        InstallMethod::Scoop
+    } else if path.starts_with(&cargo_bin) {
+        InstallMethod::Cargo
+    } else if ["/usr/bin", "/usr/sbin", "/bin"]
+        .iter()
+        .any(|dir| resolved.starts_with(dir))
+    {
+        InstallMethod::PackageManager
+    } else if receipt_dir == Some(bin_dir.as_path()) {
+        InstallMethod::Standalone(bin_dir)
+    } else if let Some(python) = python {
+        InstallMethod::Pip(python)
     } else {
-        (BASH_CMD, UV_UNIX_UNINSTALL_ARGS)
-    };
+        InstallMethod::Unknown(path.to_path_buf())
+    }
+}
 
-    println!("{}", format!("  {} {}", cmd, args.join(" ")).red());
+pub fn installed_method() -> Option<(PathBuf, InstallMethod)> {
+    let path = which::which(uv_cmd()).ok()?;
+    if let Some(record) = install_record().filter(|r| r.owns(&path)) {
+        return Some((path, InstallMethod::Archive(record)));
+    }
+    let resolved = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+    let home = PathBuf::from(shellexpand::tilde("~").as_ref());
+    let method = detect_method(&path, &resolved, &home, receipt_dir().as_deref());
+    Some((path, method))
+}
+
+pub async fn uninstall<R: std::io::Read>(input: R, clean: bool) -> Result<(), String> {
+    let (path, method) = installed_method().ok_or_else(|| "Astral UV was not found".to_string())?;
+    println!(
+        "{}",
+        format!(
+            "Found Astral UV at {} (installed with {})",
+            path.display(),
+            method
+        )
+        .yellow()
+    );
+
+    let action = method.action();
+    match &action {
+        UninstallAction::Manual(instructions) => {
+            println!(
+                "{}",
+                format!(
+                    "pypilot can not uninstall uv installed with {}, instead run:",
+                    method
+                )
+                .yellow()
+            );
+            println!("{}", format!("  {}", instructions).cyan());
+            return Ok(());
+        }
+        UninstallAction::Run(cmd, args) => {
+            println!("{}", "Uninstalling Astral UV...".yellow());
+            println!("{}", "This will run the following command:".yellow());
+            println!("{}", format!("  {} {}", cmd, args.join(" ")).red());
+        }
+        UninstallAction::Remove(files) => {
+            println!("{}", "Uninstalling Astral UV...".yellow());
+            println!("{}", "This will remove the following files:".yellow());
+            for file in files {
+                println!("{}", format!("  {}", file.display()).red());
+            }
+        }
+    }
+    if clean {
+        println!(
+            "{}",
+            "and remove the uv cache and uv managed Python installations".red()
+        );
+    }
 
     if !confirm(input) {
         println!("{}", "Exiting...".yellow());
         return Ok(());
     }
 
-    let mut child = processes::create_child_cmd(cmd, args, "");
+    if clean {
+        if let Err(e) = clean_data(&path).await {
+            eprintln!("{}", format!("Error cleaning uv data: {}", e).red());
+        }
+    }
+
+    match action {
+        UninstallAction::Run(cmd, args) => {
+            let mut child = processes::create_child_cmd(&cmd, &args, "");
+            processes::run_command(&mut child)
+                .await
+                .map_err(|_| "Uninstallation failed".to_string())?;
+        }
+        UninstallAction::Remove(files) => uvarchive::remove_files(&files)?,
+        UninstallAction::Manual(_) => {}
+    }
+    if let InstallMethod::Archive(_) = method {
+        InstallRecord::remove(&Settings::get_settings().state_dir())?;
+    }
+    Ok(())
+}
+
+async fn clean_data(uv: &Path) -> Result<(), String> {
+    let uv = uv.to_string_lossy();
+    let python_dir = processes::capture_output(&uv, &["python", "dir"]).await?;
+    let mut child = processes::create_child_cmd(&uv, &["cache", "clean"], "");
     processes::run_command(&mut child)
         .await
-        .map_err(|_| "Uninstallation failed".to_string())?;
+        .map_err(|e| e.to_string())?;
+    let python_dir = PathBuf::from(python_dir.trim());
+    if python_dir.is_dir() {
+        std::fs::remove_dir_all(&python_dir)
+            .map_err(|e| format!("Error removing {}: {}", python_dir.display(), e))?;
+        println!("Removed {}", python_dir.display());
+    }
     Ok(())
 }
 
+pub fn install_record() -> Option<InstallRecord> {
+    InstallRecord::load(&Settings::get_settings().state_dir())
+}
+
+pub fn uv_cmd() -> String {
+    Settings::get_settings().uv_cmd()
+}
+
 pub async fn check() -> bool {
-    which::which("uv").is_ok()
+    which::which(uv_cmd()).is_ok()
+}
+
+pub fn parse_version(output: &str) -> Option<Version> {
+    output.split_whitespace().nth(1)?.parse().ok()
+}
+
+pub async fn version() -> Option<Version> {
+    let output = processes::capture_output(&uv_cmd(), &["--version"])
+        .await
+        .ok()?;
+    parse_version(&output)
+}
+
+pub fn min_version() -> Version {
+    UV_MIN_VERSION
+        .parse()
+        .expect("UV_MIN_VERSION is a valid version")
+}
+
+pub fn check_min_version(installed: &Version) -> Result<(), String> {
+    let min = min_version();
+    if *installed < min {
+        return Err(format!(
+            "Astral UV {} is older than the minimum supported version {}. Please run 'pypilot install-uv --update' to upgrade it.",
+            installed, min
+        ));
+    }
+    Ok(())
+}
+
+pub async fn ensure_supported() -> Result<Version, String> {
+    let installed = version().await.ok_or_else(|| {
+        "Astral UV is not installed. Please run 'pypilot install-uv' to install it.".to_string()
+    })?;
+    check_min_version(&installed)?;
+    Ok(installed)
+}
+
+pub fn receipt_path() -> PathBuf {
+    let base = if cfg!(target_os = "windows") {
+        std::env::var("LOCALAPPDATA").unwrap_or_default()
+    } else {
+        std::env::var("XDG_CONFIG_HOME")
+            .unwrap_or_else(|_| shellexpand::tilde("~/.config").into_owned())
+    };
+    PathBuf::from(base).join("uv").join(UV_RECEIPT_FILE)
+}
+
+#[derive(serde::Deserialize)]
+struct Receipt {
+    install_prefix: PathBuf,
+    #[serde(default)]
+    install_layout: Option<String>,
+}
+
+pub fn parse_receipt(content: &str) -> Option<PathBuf> {
+    let receipt: Receipt = serde_json::from_str(content).ok()?;
+    let flat = match receipt.install_layout.as_deref() {
+        Some(layout) => layout == "flat",
+        None => receipt.install_prefix.ends_with("bin"),
+    };
+    Some(if flat {
+        receipt.install_prefix
+    } else {
+        receipt.install_prefix.join("bin")
+    })
+}
+
+pub fn receipt_dir() -> Option<PathBuf> {
+    std::fs::read_to_string(receipt_path())
+        .ok()
+        .and_then(|content| parse_receipt(&content))
+}
+
+pub fn is_standalone() -> bool {
+    matches!(installed_method(), Some((_, InstallMethod::Standalone(_))))
 }
 
 #[cfg(test)]
@@ -76,6 +438,172 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_version() {
+        let version = parse_version("uv 0.4.18 (7b55e9790 2024-10-01)").unwrap();
+        assert_eq!(version.to_string(), "0.4.18");
+        assert!(parse_version("uv").is_none());
+        assert!(parse_version("uv unknown").is_none());
+    }
+
+    #[test]
+    fn test_check_min_version() {
+        assert!(check_min_version(&min_version()).is_ok());
+        assert!(check_min_version(&"99.0".parse().unwrap()).is_ok());
+        let err = check_min_version(&"0.1.0".parse().unwrap()).unwrap_err();
+        assert!(err.contains(UV_MIN_VERSION));
+    }
+
+    #[test]
+    fn test_install_args_pinned() {
+        let version: Version = "0.4.18".parse().unwrap();
+        let (cmd, args) = install_args(Some(&version));
+        if cfg!(target_os = "windows") {
+            assert_eq!(cmd, WINGET_CMD);
+            assert_eq!(args[args.len() - 2..], ["--version", "0.4.18"]);
+        } else {
+            assert_eq!(cmd, BASH_CMD);
+            assert_eq!(
+                args[1],
+                "curl -LsSf https://astral.sh/uv/0.4.18/install.sh | sh"
+            );
+        }
+        let (_, args) = install_args(None);
+        assert!(!args.join(" ").contains("0.4.18"));
+    }
+
+    #[test]
+    fn test_detect_method() {
+        let home = Path::new("/home/dev");
+        let detect = |path: &str, resolved: &str, standalone: bool| {
+            let receipt = standalone.then(|| Path::new("/home/dev/.local/bin"));
+            detect_method(Path::new(path), Path::new(resolved), home, receipt)
+        };
+        assert_eq!(
+            detect(
+                "/home/dev/.local/bin/uv",
+                "/home/dev/.local/share/pipx/venvs/uv/bin/uv",
+                true
+            ),
+            InstallMethod::Pipx
+        );
+        assert_eq!(
+            detect(
+                "/opt/homebrew/bin/uv",
+                "/opt/homebrew/Cellar/uv/0.4.18/bin/uv",
+                false
+            ),
+            InstallMethod::Homebrew
+        );
+        assert_eq!(
+            detect("/home/dev/.local/bin/uv", "/home/dev/.local/bin/uv", true),
+            InstallMethod::Standalone(PathBuf::from("/home/dev/.local/bin"))
+        );
+        assert_eq!(
+            detect("/usr/bin/uv", "/usr/bin/uv", false),
+            InstallMethod::PackageManager
+        );
+        assert_eq!(
+            detect("/usr/bin/uv", "/usr/bin/uv", true),
+            InstallMethod::PackageManager
+        );
+        if std::env::var_os("CARGO_HOME").is_none() {
+            let cargo_uv = Path::new("/home/dev/.cargo/bin/uv");
+            assert_eq!(
+                detect_method(cargo_uv, cargo_uv, home, cargo_uv.parent()),
+                InstallMethod::Cargo
+            );
+        }
+        assert_eq!(
+            detect("/opt/tools/uv", "/opt/tools/uv", true),
+            InstallMethod::Unknown(PathBuf::from("/opt/tools/uv"))
+        );
+        assert_eq!(
+            detect("/opt/tools/uv", "/opt/tools/uv", false),
+            InstallMethod::Unknown(PathBuf::from("/opt/tools/uv"))
+        );
+        assert_eq!(
+            detect(
+                "C:\\Users\\dev\\scoop\\shims\\uv.exe",
+                "C:\\Users\\dev\\scoop\\apps\\uv\\current\\uv.exe",
+                false
+            ),
+            InstallMethod::Scoop
+        );
+        if std::env::var_os("CARGO_HOME").is_none() {
+            assert_eq!(
+                detect("/home/dev/.cargo/bin/uv", "/home/dev/.cargo/bin/uv", false),
+                InstallMethod::Cargo
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_receipt() {
+        assert_eq!(
+            parse_receipt(
+                r#"{"binaries":["uv","uvx"],"install_prefix":"/home/dev/.local/bin","install_layout":"flat","version":"0.4.18"}"#
+            ),
+            Some(PathBuf::from("/home/dev/.local/bin"))
+        );
+        assert_eq!(
+            parse_receipt(r#"{"install_prefix":"/home/dev/.cargo","install_layout":"cargo-home"}"#),
+            Some(PathBuf::from("/home/dev/.cargo/bin"))
+        );
+        assert_eq!(
+            parse_receipt(r#"{"install_prefix":"/home/dev/.local/bin"}"#),
+            Some(PathBuf::from("/home/dev/.local/bin"))
+        );
+        assert_eq!(parse_receipt("{}"), None);
+    }
+
+    #[test]
+    fn test_detect_method_pip() {
+        let dir = std::env::temp_dir().join(format!("pypilot_uv_pip_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("python3"), "").unwrap();
+        let uv = dir.join("uv");
+        let method = detect_method(&uv, &uv, Path::new("/home/dev"), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(method, InstallMethod::Pip(dir.join("python3")));
+        match method.action() {
+            UninstallAction::Manual(cmd) => assert!(cmd.ends_with("-m pip uninstall uv")),
+            other => panic!("Expected manual instructions, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_install_method_action() {
+        assert_eq!(
+            InstallMethod::Cargo.action(),
+            UninstallAction::Run(
+                "cargo".to_string(),
+                vec!["uninstall".to_string(), "uv".to_string()]
+            )
+        );
+        let record = InstallRecord {
+            binaries: vec![PathBuf::from("/opt/bin/uv")],
+            ..InstallRecord::default()
+        };
+        assert_eq!(
+            InstallMethod::Archive(record).action(),
+            UninstallAction::Remove(vec![PathBuf::from("/opt/bin/uv")])
+        );
+        assert!(matches!(
+            InstallMethod::PackageManager.action(),
+            UninstallAction::Manual(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_version() {
+        if !check().await {
+            println!("Skipping test because Astral UV is not installed");
+            return;
+        }
+        assert!(version().await.is_some());
+    }
+
     #[tokio::test]
     async fn test_install_uv_yes() {
         if std::env::var("GITHUB_ACTIONS").is_err() {
@@ -85,7 +613,9 @@ mod tests {
         #[cfg(unix)]
         {
             let cursor = std::io::Cursor::new("y\n");
-            install(cursor).await.expect("Failed to install Astral UV");
+            install(cursor, None)
+                .await
+                .expect("Failed to install Astral UV");
         }
     }
 
@@ -96,7 +626,9 @@ mod tests {
             return;
         }
         let cursor = std::io::Cursor::new("n\n");
-        install(cursor).await.expect("Failed to install Astral UV");
+        install(cursor, None)
+            .await
+            .expect("Failed to install Astral UV");
     }
 
     #[tokio::test]
@@ -106,7 +638,7 @@ mod tests {
             return;
         }
         let cursor = std::io::Cursor::new("n\n");
-        uninstall(cursor)
+        uninstall(cursor, false)
             .await
             .expect("Failed to uninstall Astral UV");
     }
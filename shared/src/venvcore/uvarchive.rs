@@ -0,0 +1,425 @@
+use crate::constants::UV_INSTALL_RECORD_FILE;
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+#[cfg(windows)]
+pub const UV_BINARIES: &[&str] = &["uv.exe", "uvx.exe", "uvw.exe"];
+#[cfg(not(windows))]
+pub const UV_BINARIES: &[&str] = &["uv", "uvx"];
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct InstallRecord {
+    pub bin_dir: PathBuf,
+    pub binaries: Vec<PathBuf>,
+    pub source: String,
+    pub sha256: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub installed: Option<DateTime<Utc>>,
+}
+
+impl InstallRecord {
+    pub fn load(state_dir: &Path) -> Option<Self> {
+        let content = fs::read_to_string(state_dir.join(UV_INSTALL_RECORD_FILE)).ok()?;
+        toml::from_str(&content).ok()
+    }
+
+    pub fn save(&self, state_dir: &Path) -> Result<(), String> {
+        fs::create_dir_all(state_dir).map_err(|e| format!("Error creating state dir: {}", e))?;
+        let content = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::write(state_dir.join(UV_INSTALL_RECORD_FILE), content)
+            .map_err(|e| format!("Error writing install record: {}", e))
+    }
+
+    pub fn remove(state_dir: &Path) -> Result<(), String> {
+        match fs::remove_file(state_dir.join(UV_INSTALL_RECORD_FILE)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                Err(format!("Error removing install record: {}", e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn owns(&self, path: &Path) -> bool {
+        let Ok(path) = fs::canonicalize(path) else {
+            return false;
+        };
+        self.binaries
+            .iter()
+            .any(|b| fs::canonicalize(b).is_ok_and(|b| b == path))
+    }
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file =
+        fs::File::open(path).map_err(|e| format!("Error opening {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)
+        .map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn parse_checksum(text: &str) -> Result<String, String> {
+    let checksum = text.split_whitespace().next().unwrap_or_default();
+    if checksum.len() != 64 || !checksum.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid SHA-256 checksum '{}'", checksum));
+    }
+    Ok(checksum.to_lowercase())
+}
+
+pub fn expected_checksum(checked: &Path, given: Option<&str>) -> Result<String, String> {
+    if let Some(given) = given {
+        return parse_checksum(given);
+    }
+    let mut sidecar = checked.as_os_str().to_owned();
+    sidecar.push(".sha256");
+    let sidecar = PathBuf::from(sidecar);
+    let content = fs::read_to_string(&sidecar).map_err(|_| {
+        format!(
+            "No checksum given for {}: pass --sha256 or place it in {}",
+            checked.display(),
+            sidecar.display()
+        )
+    })?;
+    parse_checksum(&content)
+}
+
+pub fn verify(checked: &Path, given: Option<&str>) -> Result<String, String> {
+    let expected = expected_checksum(checked, given)?;
+    let actual = sha256_file(checked)?;
+    if actual != expected {
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            checked.display(),
+            expected,
+            actual
+        ));
+    }
+    Ok(actual)
+}
+
+type Binaries = Vec<(String, Vec<u8>)>;
+
+fn wanted(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    UV_BINARIES.contains(&name).then(|| name.to_string())
+}
+
+fn find_in_dir(dir: &Path, name: &str) -> Option<PathBuf> {
+    let candidate = dir.join(name);
+    if candidate.is_file() {
+        return Some(candidate);
+    }
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .find_map(|e| find_in_dir(&e.path(), name))
+}
+
+fn read_dir_source(dir: &Path, sha256: Option<&str>) -> Result<(String, Binaries), String> {
+    let uv = find_in_dir(dir, UV_BINARIES[0])
+        .ok_or_else(|| format!("No {} binary found in {}", UV_BINARIES[0], dir.display()))?;
+    let checksum = verify(&uv, sha256)?;
+    let mut binaries = Vec::new();
+    for name in UV_BINARIES {
+        let Some(path) = find_in_dir(dir, name) else {
+            continue;
+        };
+        if *name != UV_BINARIES[0] {
+            verify(&path, None).map_err(|e| {
+                format!(
+                    "{}, every binary in a directory source needs its own checksum",
+                    e
+                )
+            })?;
+        }
+        let data =
+            fs::read(&path).map_err(|e| format!("Error reading {}: {}", path.display(), e))?;
+        binaries.push((name.to_string(), data));
+    }
+    Ok((checksum, binaries))
+}
+
+fn read_tar_gz(path: &Path) -> Result<Binaries, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut binaries = Vec::new();
+    let entries = archive.entries().map_err(|e| e.to_string())?;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| e.to_string())?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let Some(name) = entry.path().ok().and_then(|p| wanted(&p)) else {
+            continue;
+        };
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(|e| e.to_string())?;
+        binaries.push((name, data));
+    }
+    Ok(binaries)
+}
+
+fn read_zip(path: &Path) -> Result<Binaries, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut binaries = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+        if !entry.is_file() {
+            continue;
+        }
+        let Some(name) = entry.enclosed_name().and_then(|p| wanted(&p)) else {
+            continue;
+        };
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data).map_err(|e| e.to_string())?;
+        binaries.push((name, data));
+    }
+    Ok(binaries)
+}
+
+fn read_archive(path: &Path) -> Result<Binaries, String> {
+    let name = path.to_string_lossy().to_lowercase();
+    let result = if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        read_tar_gz(path)
+    } else if name.ends_with(".zip") {
+        read_zip(path)
+    } else {
+        return Err(format!(
+            "Unsupported archive format for {}, expected .tar.gz or .zip",
+            path.display()
+        ));
+    };
+    result.map_err(|e| format!("Error reading {}: {}", path.display(), e))
+}
+
+fn write_binary(bin_dir: &Path, name: &str, data: &[u8]) -> Result<PathBuf, String> {
+    let target = bin_dir.join(name);
+    let temp = bin_dir.join(format!(".{}.pypilot-tmp", name));
+    fs::write(&temp, data).map_err(|e| format!("Error writing {}: {}", temp.display(), e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&temp, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Error setting permissions on {}: {}", temp.display(), e))?;
+    }
+    fs::rename(&temp, &target).map_err(|e| {
+        let _ = fs::remove_file(&temp);
+        format!("Error installing {}: {}", target.display(), e)
+    })?;
+    Ok(target)
+}
+
+pub fn install_from(
+    source: &Path,
+    sha256: Option<&str>,
+    bin_dir: &Path,
+) -> Result<InstallRecord, String> {
+    let (checksum, binaries) = if source.is_dir() {
+        read_dir_source(source, sha256)?
+    } else if source.is_file() {
+        (verify(source, sha256)?, read_archive(source)?)
+    } else {
+        return Err(format!("{} does not exist", source.display()));
+    };
+    if !binaries.iter().any(|(name, _)| name == UV_BINARIES[0]) {
+        return Err(format!(
+            "No {} binary found in {}",
+            UV_BINARIES[0],
+            source.display()
+        ));
+    }
+    fs::create_dir_all(bin_dir)
+        .map_err(|e| format!("Error creating {}: {}", bin_dir.display(), e))?;
+    let installed = binaries
+        .iter()
+        .map(|(name, data)| write_binary(bin_dir, name, data))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(InstallRecord {
+        bin_dir: bin_dir.to_path_buf(),
+        binaries: installed,
+        source: fs::canonicalize(source)
+            .unwrap_or_else(|_| source.to_path_buf())
+            .display()
+            .to_string(),
+        sha256: checksum,
+        installed: Some(Utc::now()),
+    })
+}
+
+pub fn remove_files(files: &[PathBuf]) -> Result<(), String> {
+    for file in files {
+        match fs::remove_file(file) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                return Err(format!("Error removing {}: {}", file.display(), e));
+            }
+            _ => println!("Removed {}", file.display()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("pypilot_uvarchive_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn fake_tar_gz(path: &Path) {
+        let file = fs::File::create(path).unwrap();
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        for name in UV_BINARIES {
+            let data = format!("fake {}", name);
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder
+                .append_data(
+                    &mut header,
+                    format!("uv-x86_64-unknown-linux-gnu/{}", name),
+                    data.as_bytes(),
+                )
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_install_from_tar_gz() {
+        let dir = temp_dir("tar");
+        let archive = dir.join("uv.tar.gz");
+        fake_tar_gz(&archive);
+        let checksum = sha256_file(&archive).unwrap();
+        let bin_dir = dir.join("bin");
+
+        let record = install_from(&archive, Some(&checksum), &bin_dir).unwrap();
+        assert_eq!(record.binaries.len(), UV_BINARIES.len());
+        assert_eq!(record.sha256, checksum);
+        let uv = fs::read_to_string(bin_dir.join(UV_BINARIES[0])).unwrap();
+        assert_eq!(uv, format!("fake {}", UV_BINARIES[0]));
+
+        remove_files(&record.binaries).unwrap();
+        assert!(!bin_dir.join(UV_BINARIES[0]).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_from_checksum_mismatch() {
+        let dir = temp_dir("mismatch");
+        let archive = dir.join("uv.tar.gz");
+        fake_tar_gz(&archive);
+        let bin_dir = dir.join("bin");
+
+        let err = install_from(&archive, Some(&"0".repeat(64)), &bin_dir).unwrap_err();
+        assert!(err.contains("Checksum mismatch"));
+        assert!(!bin_dir.exists());
+        let err = install_from(&archive, None, &bin_dir).unwrap_err();
+        assert!(err.contains("No checksum given"));
+        assert!(install_from(&archive, Some("nothex"), &bin_dir).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_from_sidecar_and_zip() {
+        let dir = temp_dir("zip");
+        let archive = dir.join("uv.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        writer
+            .start_file(
+                format!("uv-x86_64-pc-windows-msvc/{}", UV_BINARIES[0]),
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(b"fake uv").unwrap();
+        writer.finish().unwrap();
+        let checksum = sha256_file(&archive).unwrap();
+        fs::write(dir.join("uv.zip.sha256"), format!("{}  uv.zip\n", checksum)).unwrap();
+
+        let record = install_from(&archive, None, &dir.join("bin")).unwrap();
+        assert_eq!(record.binaries, vec![dir.join("bin").join(UV_BINARIES[0])]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_from_dir() {
+        let dir = temp_dir("dir");
+        let source = dir.join("source");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join(UV_BINARIES[0]), "fake uv").unwrap();
+        let checksum = sha256_file(&source.join(UV_BINARIES[0])).unwrap();
+
+        let record = install_from(&source, Some(&checksum), &dir.join("bin")).unwrap();
+        assert_eq!(record.binaries.len(), 1);
+        assert!(install_from(&dir.join("missing"), Some(&checksum), &dir.join("bin")).is_err());
+
+        let uvx = source.join(UV_BINARIES[1]);
+        fs::write(&uvx, "fake uvx").unwrap();
+        let err = install_from(&source, Some(&checksum), &dir.join("bin2")).unwrap_err();
+        assert!(err.contains("every binary"));
+        assert!(!dir.join("bin2").exists());
+        let mut sidecar = uvx.as_os_str().to_owned();
+        sidecar.push(".sha256");
+        fs::write(&sidecar, "0".repeat(64)).unwrap();
+        let err = install_from(&source, Some(&checksum), &dir.join("bin2")).unwrap_err();
+        assert!(err.contains("Checksum mismatch"));
+        fs::write(&sidecar, sha256_file(&uvx).unwrap()).unwrap();
+        let record = install_from(&source, Some(&checksum), &dir.join("bin2")).unwrap();
+        assert_eq!(record.binaries.len(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_record_save_and_load() {
+        let dir = temp_dir("record");
+        assert!(InstallRecord::load(&dir).is_none());
+        let record = InstallRecord {
+            bin_dir: dir.join("bin"),
+            binaries: vec![dir.join("bin/uv")],
+            source: "uv.tar.gz".to_string(),
+            sha256: "0".repeat(64),
+            installed: None,
+        };
+        record.save(&dir).unwrap();
+        assert_eq!(InstallRecord::load(&dir), Some(record));
+        InstallRecord::remove(&dir).unwrap();
+        assert!(InstallRecord::load(&dir).is_none());
+        InstallRecord::remove(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_install_record_owns() {
+        let dir = temp_dir("owns");
+        fs::create_dir_all(dir.join("bin")).unwrap();
+        fs::write(dir.join("bin/uv"), "fake uv").unwrap();
+        fs::write(dir.join("other-uv"), "fake uv").unwrap();
+        let record = InstallRecord {
+            bin_dir: dir.join("bin"),
+            binaries: vec![dir.join("bin/uv"), dir.join("bin/uvx")],
+            ..InstallRecord::default()
+        };
+        let owns_installed = record.owns(&dir.join("bin/../bin/uv"));
+        let owns_other = record.owns(&dir.join("other-uv"));
+        let owns_missing = record.owns(&dir.join("bin/uvx"));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(owns_installed);
+        assert!(!owns_other);
+        assert!(!owns_missing);
+    }
+}
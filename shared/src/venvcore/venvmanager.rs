@@ -1,16 +1,30 @@
-use super::venv::{self, Venv};
+use super::{
+    audit::Finding,
+    diff::{Change, PackageDiff},
+    info::{Column, VenvInfo},
+    licenses::{PackageLicense, Verdict},
+    outdated::{Bump, Outdated},
+    picker,
+    snapshot::Snapshot,
+    usage::UsageReport,
+    venv::{self, Venv},
+};
 use crate::{
     constants::{UNIX_PYTHON3_EXEC, UNIX_PYTHON_EXEC, WIN_PYTHON_EXEC},
-    settings,
+    settings, utils, validation,
 };
 use colored::Colorize;
-use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, ContentArrangement, Table};
+use comfy_table::{
+    modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL, Cell, Color, ContentArrangement, Table,
+};
 use once_cell::sync::Lazy;
-use std::io::{self, BufRead, Write};
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::{fs, io::stdout};
 
 pub struct VenvManager;
 
+const EXACT_NAME_METHODS: &[&str] = &["delete", "rename"];
+
 pub static VENVMANAGER: Lazy<VenvManager> = Lazy::new(VenvManager::new);
 
 impl VenvManager {
@@ -19,18 +33,32 @@ impl VenvManager {
     }
 
     pub async fn list(&self) -> Vec<Venv> {
-        let path = shellexpand::tilde(&settings::Settings::get_settings().venvs_path).to_string();
-        let venvs: Vec<Venv> = match fs::read_dir(&path) {
+        let path = settings::Settings::get_settings().venvs_path;
+        let mut venvs: Vec<Venv> = match fs::read_dir(&path) {
             Ok(entries) => self.collect_venvs(entries),
             Err(_) => Vec::new(),
         };
+        venvs.sort_by(|a, b| a.name.cmp(&b.name));
         venvs
     }
 
+    pub async fn collect_info(&self, venvs: Vec<Venv>, columns: &[Column]) -> Vec<VenvInfo> {
+        let handles: Vec<_> = venvs
+            .into_iter()
+            .map(|venv| tokio::spawn(VenvInfo::collect(venv, columns.to_vec())))
+            .collect();
+        let mut infos = Vec::with_capacity(handles.len());
+        for handle in handles {
+            if let Ok(info) = handle.await {
+                infos.push(info);
+            }
+        }
+        infos
+    }
+
     pub async fn check_if_exists(&self, name: String) -> bool {
-        let path = shellexpand::tilde(&settings::Settings::get_settings().venvs_path).to_string();
-        let venv_path = format!("{}/{}", path, name);
-        std::path::Path::new(&venv_path).exists()
+        let path = settings::Settings::get_settings().venvs_path;
+        path.join(name).exists()
     }
 
     pub async fn find_venv(
@@ -40,28 +68,37 @@ impl VenvManager {
         method: &str,
     ) -> Option<Venv> {
         let venv = match name.or(name_pos) {
-            Some(n) => venv::Venv::new(n, "".to_string(), "".to_string(), vec![], false),
+            Some(n) => {
+                let venvs = self.list().await;
+                match self.resolve_name(&n, &venvs, method).await {
+                    Ok(venv) => venv,
+                    Err(e) => {
+                        eprintln!("{}", e.red());
+                        return None;
+                    }
+                }
+            }
             None => {
-                let mut venvs = self.list().await;
+                let venvs = self.list().await;
                 if venvs.is_empty() {
                     println!("{}", "No virtual environments found".yellow());
                     return None;
                 }
-                self.print_venv_table(&mut venvs).await;
-                println!(
-                    "{} {}{}",
-                    "Please select a virtual environment to".cyan(),
-                    method.yellow(),
-                    " (c to cancel):".cyan()
-                );
-                match self.get_index(io::stdin(), venvs.len()) {
-                    Ok(index) => venv::Venv::new(
-                        venvs[index - 1].name.clone(),
-                        "".to_string(),
-                        "".to_string(),
-                        vec![],
-                        false,
-                    ),
+                let mut venvs = self.collect_info(venvs, &[]).await;
+                let selected = if io::stdin().is_terminal() && io::stdout().is_terminal() {
+                    picker::pick(&venvs, method)
+                } else {
+                    self.print_venv_table(&venvs, &[]);
+                    println!(
+                        "{} {}{}",
+                        "Please select a virtual environment to".cyan(),
+                        method.yellow(),
+                        " (c to cancel):".cyan()
+                    );
+                    self.get_index(io::stdin(), venvs.len()).map(|i| i - 1)
+                };
+                match selected {
+                    Ok(index) => venvs.swap_remove(index).venv,
                     Err(e) => {
                         println!("{}", e.yellow());
                         return None;
@@ -72,6 +109,144 @@ impl VenvManager {
         Some(venv)
     }
 
+    pub async fn find_venvs(&self, patterns: Vec<String>, method: &str) -> Vec<Venv> {
+        let venvs = self.list().await;
+        if patterns.is_empty() {
+            if venvs.is_empty() {
+                println!("{}", "No virtual environments found".yellow());
+                return Vec::new();
+            }
+            let infos = self.collect_info(venvs, &[]).await;
+            let selected = if io::stdin().is_terminal() && io::stdout().is_terminal() {
+                picker::pick_many(&infos, method)
+            } else {
+                self.print_venv_table(&infos, &[]);
+                println!(
+                    "{} {}{}",
+                    "Please select virtual environments to".cyan(),
+                    method.yellow(),
+                    " (separate with spaces, c to cancel):".cyan()
+                );
+                self.get_indices(io::stdin(), infos.len())
+                    .map(|indices| indices.iter().map(|i| i - 1).collect())
+            };
+            return match selected {
+                Ok(indices) => indices.iter().map(|i| infos[*i].venv.clone()).collect(),
+                Err(e) => {
+                    println!("{}", e.yellow());
+                    Vec::new()
+                }
+            };
+        }
+        let mut selected: Vec<Venv> = Vec::new();
+        let mut add = |venv: Venv| {
+            if !selected.iter().any(|v| v.name == venv.name) {
+                selected.push(venv);
+            }
+        };
+        for pattern in patterns {
+            if pattern.contains(['*', '?', '[']) {
+                let glob = match glob::Pattern::new(&pattern) {
+                    Ok(glob) => glob,
+                    Err(e) => {
+                        eprintln!("{}", format!("Invalid pattern '{}': {}", pattern, e).red());
+                        return Vec::new();
+                    }
+                };
+                let matched: Vec<&Venv> = venvs.iter().filter(|v| glob.matches(&v.name)).collect();
+                if matched.is_empty() {
+                    println!(
+                        "{}",
+                        format!("No virtual environments match '{}'", pattern).yellow()
+                    );
+                }
+                matched.into_iter().cloned().for_each(&mut add);
+                continue;
+            }
+            match self.resolve_name(&pattern, &venvs, method).await {
+                Ok(venv) => add(venv),
+                Err(e) => {
+                    eprintln!("{}", e.red());
+                    return Vec::new();
+                }
+            }
+        }
+        selected
+    }
+
+    async fn resolve_name(&self, name: &str, venvs: &[Venv], method: &str) -> Result<Venv, String> {
+        validation::validate_name(name)?;
+        let exact = || venv::Venv::new(name.to_string(), "".to_string(), vec![], false);
+        if self.check_if_exists(name.to_string()).await {
+            return Ok(exact());
+        }
+        let names: Vec<&str> = venvs.iter().map(|v| v.name.as_str()).collect();
+        match self.resolve_prefix(name, &names)? {
+            None => Ok(exact()),
+            Some(index) if EXACT_NAME_METHODS.contains(&method) => Err(format!(
+                "Virtual environment '{}' does not exist, use the full name '{}' to {}",
+                name, names[index], method
+            )),
+            Some(index) => {
+                println!(
+                    "{} {}",
+                    "Using virtual environment:".cyan(),
+                    venvs[index].name.green()
+                );
+                Ok(venvs[index].clone())
+            }
+        }
+    }
+
+    fn resolve_prefix(&self, prefix: &str, names: &[&str]) -> Result<Option<usize>, String> {
+        let matches: Vec<usize> = names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| name.starts_with(prefix))
+            .map(|(i, _)| i)
+            .collect();
+        match matches.as_slice() {
+            [] => Ok(None),
+            [index] => Ok(Some(*index)),
+            _ => Err(format!(
+                "'{}' is ambiguous, it matches: {}",
+                prefix,
+                matches
+                    .iter()
+                    .map(|i| names[*i])
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+
+    fn get_indices<R: std::io::Read>(&self, input: R, size: usize) -> Result<Vec<usize>, String> {
+        let mut input_string = String::new();
+        let mut stdin = std::io::BufReader::new(input);
+        let _ = stdout().flush();
+        let _ = stdin.read_line(&mut input_string).is_ok();
+        let trimmed = input_string.trim();
+        if trimmed.eq_ignore_ascii_case("q") || trimmed.eq_ignore_ascii_case("c") {
+            return Err("Cancelled by user".to_string());
+        }
+        let mut indices = Vec::new();
+        for part in trimmed.split([',', ' ']).filter(|p| !p.is_empty()) {
+            let idx = part
+                .parse::<usize>()
+                .map_err(|_| "Error: please provide valid numbers!".to_string())?;
+            if !(1..=size).contains(&idx) {
+                return Err("Error: index out of range!".to_string());
+            }
+            if !indices.contains(&idx) {
+                indices.push(idx);
+            }
+        }
+        if indices.is_empty() {
+            return Err("Error: please provide valid numbers!".to_string());
+        }
+        Ok(indices)
+    }
+
     fn get_index<R: std::io::Read>(&self, input: R, size: usize) -> Result<usize, String> {
         let mut input_string = String::new();
         let mut stdin = std::io::BufReader::new(input);
@@ -95,21 +270,18 @@ impl VenvManager {
         let venvs: Vec<Venv> = entries
             .filter_map(Result::ok)
             .filter_map(|entry| {
-                if entry.file_type().ok()?.is_dir() {
+                let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if entry.file_type().ok()?.is_dir() && !hidden {
                     let dir_path = entry.path();
                     let python_paths = [
                         dir_path.join(WIN_PYTHON_EXEC),
                         dir_path.join(UNIX_PYTHON_EXEC),
                         dir_path.join(UNIX_PYTHON3_EXEC),
                     ];
-                    if python_paths.iter().any(|p| p.exists()) {
-                        Some(Venv::new(
-                            entry.file_name().to_str()?.to_string(),
-                            dir_path.to_str()?.to_string(),
-                            "".to_string(),
-                            vec![],
-                            false,
-                        ))
+                    if dir_path.join("pyvenv.cfg").is_file()
+                        || python_paths.iter().any(|p| p.symlink_metadata().is_ok())
+                    {
+                        Some(Venv::from_path(dir_path))
                     } else {
                         None
                     }
@@ -121,33 +293,320 @@ impl VenvManager {
         venvs
     }
 
-    pub async fn print_venv_table(&self, venvs: &mut [Venv]) {
-        self.print_venv_table_to(&mut std::io::stdout(), venvs)
-            .await;
+    pub fn print_venv_table(&self, venvs: &[VenvInfo], columns: &[Column]) {
+        self.print_venv_table_to(&mut std::io::stdout(), venvs, columns);
     }
 
-    async fn print_venv_table_to<W: Write>(&self, writer: &mut W, venvs: &mut [Venv]) {
+    fn print_venv_table_to<W: Write>(
+        &self,
+        writer: &mut W,
+        venvs: &[VenvInfo],
+        columns: &[Column],
+    ) {
+        let mut header = vec!["Index", "Name", "Version"];
+        header.extend(columns.iter().map(Column::header));
         let mut table = Table::new();
         table
             .load_preset(UTF8_FULL)
             .apply_modifier(UTF8_ROUND_CORNERS)
             .set_content_arrangement(ContentArrangement::Dynamic)
-            .set_header(vec!["Index", "Name", "Version"]);
-        for (index, venv) in venvs.iter_mut().enumerate() {
-            venv.set_python_version().await;
-            table.add_row(vec![
+            .set_header(header);
+        for (index, info) in venvs.iter().enumerate() {
+            let mut row = vec![
                 (index + 1).to_string(),
-                venv.name.clone(),
-                venv.python_version.clone(),
+                info.venv.name.clone(),
+                info.venv.python_version.clone(),
+            ];
+            row.extend(columns.iter().map(|c| info.cell(*c)));
+            table.add_row(row);
+        }
+        writeln!(writer, "{}", table).unwrap();
+    }
+
+    pub fn print_details(&self, info: &VenvInfo, snapshots: &[Snapshot]) {
+        self.print_details_to(&mut std::io::stdout(), info, snapshots);
+    }
+
+    fn print_details_to<W: Write>(&self, writer: &mut W, info: &VenvInfo, snapshots: &[Snapshot]) {
+        let venv = &info.venv;
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic);
+        let rows = [
+            ("Name", venv.name.clone()),
+            ("Version", venv.python_version.clone()),
+            ("Path", venv.path.display().to_string()),
+            ("Size", info.cell(Column::Size)),
+            ("Packages", info.cell(Column::Packages)),
+            ("Created", info.cell(Column::Created)),
+            ("Used", info.cell(Column::Used)),
+            ("Tags", info.cell(Column::Tags)),
+            ("Description", info.cell(Column::Description)),
+            (
+                "Index",
+                optional(venv.index_url.clone().or(venv.index_profile.clone())),
+            ),
+            (
+                "Wheelhouse",
+                optional(venv.wheelhouse.as_ref().map(|w| w.display().to_string())),
+            ),
+        ];
+        for (key, value) in rows {
+            table.add_row(vec![key.to_string(), value]);
+        }
+        writeln!(writer, "{}", table).unwrap();
+        if snapshots.is_empty() {
+            writeln!(writer, "No snapshots").unwrap();
+            return;
+        }
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec!["Snapshot", "Label", "Created", "Packages", "Archive"]);
+        for snapshot in snapshots.iter().rev() {
+            let archive = snapshot
+                .archive
+                .as_ref()
+                .and_then(|a| fs::metadata(a).ok())
+                .map(|m| utils::format_size(m.len()));
+            table.add_row(vec![
+                snapshot.id.clone(),
+                optional(snapshot.label.clone()),
+                utils::format_time(&snapshot.created),
+                snapshot.packages.len().to_string(),
+                optional(archive),
+            ]);
+        }
+        writeln!(writer, "{}", table).unwrap();
+    }
+
+    pub fn print_diff_table(&self, diff: &PackageDiff) {
+        self.print_diff_table_to(&mut std::io::stdout(), diff);
+    }
+
+    fn print_diff_table_to<W: Write>(&self, writer: &mut W, diff: &PackageDiff) {
+        if diff.entries.is_empty() {
+            writeln!(
+                writer,
+                "{} and {} have the same packages",
+                diff.left, diff.right
+            )
+            .unwrap();
+            return;
+        }
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec!["Package", diff.left.as_str(), diff.right.as_str()]);
+        for entry in &diff.entries {
+            let color = match entry.change {
+                Change::OnlyLeft => Color::Red,
+                Change::OnlyRight => Color::Green,
+                Change::Changed => Color::Yellow,
+            };
+            let value = |v: &Option<String>| v.clone().unwrap_or_else(|| "-".to_string());
+            table.add_row(vec![
+                Cell::new(&entry.name).fg(color),
+                Cell::new(value(&entry.left)),
+                Cell::new(value(&entry.right)),
+            ]);
+        }
+        writeln!(writer, "{}", table).unwrap();
+    }
+
+    pub fn print_outdated_table(&self, report: &[(String, Vec<Outdated>)]) {
+        self.print_outdated_table_to(&mut std::io::stdout(), report);
+    }
+
+    fn print_outdated_table_to<W: Write>(
+        &self,
+        writer: &mut W,
+        report: &[(String, Vec<Outdated>)],
+    ) {
+        let multiple = report.len() > 1;
+        let mut header = vec!["Package", "Installed", "Latest", "Bump"];
+        if multiple {
+            header.insert(0, "Environment");
+        }
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(header);
+        for (name, packages) in report {
+            for package in packages {
+                let color = match package.bump {
+                    Bump::Major => Color::Red,
+                    Bump::Minor => Color::Yellow,
+                    Bump::Patch | Bump::Other => Color::Green,
+                };
+                let mut row = vec![
+                    Cell::new(&package.name),
+                    Cell::new(package.installed.to_string()),
+                    Cell::new(package.latest.to_string()),
+                    Cell::new(package.bump.to_string()).fg(color),
+                ];
+                if multiple {
+                    row.insert(0, Cell::new(name));
+                }
+                table.add_row(row);
+            }
+        }
+        writeln!(writer, "{}", table).unwrap();
+    }
+
+    pub fn print_audit_table(&self, report: &[(String, Vec<Finding>)]) {
+        self.print_audit_table_to(&mut std::io::stdout(), report);
+    }
+
+    fn print_audit_table_to<W: Write>(&self, writer: &mut W, report: &[(String, Vec<Finding>)]) {
+        let multiple = report.len() > 1;
+        let mut header = vec![
+            "Package",
+            "Installed",
+            "Advisory",
+            "Severity",
+            "Fixed in",
+            "Summary",
+        ];
+        if multiple {
+            header.insert(0, "Environment");
+        }
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(header);
+        for (name, findings) in report {
+            for finding in findings {
+                let severity = finding.severity.clone().unwrap_or("-".to_string());
+                let color = match severity.to_uppercase().as_str() {
+                    "CRITICAL" | "HIGH" => Color::Red,
+                    "MODERATE" | "MEDIUM" => Color::Yellow,
+                    "LOW" => Color::Green,
+                    _ => Color::Reset,
+                };
+                let mut advisory = vec![finding.id.clone()];
+                advisory.extend(finding.aliases.iter().cloned());
+                let mut row = vec![
+                    Cell::new(&finding.package),
+                    Cell::new(finding.installed.to_string()).fg(Color::Red),
+                    Cell::new(advisory.join("\n")),
+                    Cell::new(severity).fg(color),
+                    Cell::new(
+                        finding
+                            .fixed
+                            .as_ref()
+                            .map(|v| v.to_string())
+                            .unwrap_or("-".to_string()),
+                    )
+                    .fg(Color::Green),
+                    Cell::new(&finding.summary),
+                ];
+                if multiple {
+                    row.insert(0, Cell::new(name));
+                }
+                table.add_row(row);
+            }
+        }
+        writeln!(writer, "{}", table).unwrap();
+    }
+
+    pub fn print_license_table(&self, packages: &[PackageLicense], policy: bool) {
+        self.print_license_table_to(&mut std::io::stdout(), packages, policy);
+    }
+
+    fn print_license_table_to<W: Write>(
+        &self,
+        writer: &mut W,
+        packages: &[PackageLicense],
+        policy: bool,
+    ) {
+        let mut header = vec!["Package", "Version", "License"];
+        if policy {
+            header.push("Status");
+        }
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(header);
+        for package in packages {
+            let mut row = vec![
+                Cell::new(&package.name),
+                Cell::new(&package.version),
+                Cell::new(&package.license),
+            ];
+            if policy {
+                let color = match package.verdict {
+                    Verdict::Allowed => Color::Green,
+                    Verdict::Denied | Verdict::NotAllowed => Color::Red,
+                };
+                row.push(Cell::new(package.verdict.to_string()).fg(color));
+            }
+            table.add_row(row);
+        }
+        writeln!(writer, "{}", table).unwrap();
+    }
+
+    pub fn print_usage_table(&self, report: &UsageReport) {
+        self.print_usage_table_to(&mut std::io::stdout(), report);
+    }
+
+    fn print_usage_table_to<W: Write>(&self, writer: &mut W, report: &UsageReport) {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .apply_modifier(UTF8_ROUND_CORNERS)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec!["Name", "Total", "Unique", "Shared"]);
+        for env in &report.envs {
+            table.add_row(vec![
+                env.name.clone(),
+                utils::format_size(env.total),
+                utils::format_size(env.unique),
+                utils::format_size(env.shared),
             ]);
         }
         writeln!(writer, "{}", table).unwrap();
+        writeln!(
+            writer,
+            "Apparent size: {}, actual disk usage: {}",
+            utils::format_size(report.apparent),
+            utils::format_size(report.actual)
+        )
+        .unwrap();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::venvcore::doctor;
+
+    fn to_infos(venvs: Vec<Venv>) -> Vec<VenvInfo> {
+        venvs
+            .into_iter()
+            .map(|venv| VenvInfo {
+                venv,
+                size: None,
+                packages: None,
+                created: None,
+                used: None,
+                status: None,
+            })
+            .collect()
+    }
 
     #[tokio::test]
     async fn test_list_venvs() {
@@ -159,6 +618,25 @@ mod tests {
         assert!(venvs.is_empty());
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_collect_venvs_with_dangling_python() {
+        let root = std::env::temp_dir().join(format!("pypilot_collect_{}", std::process::id()));
+        let broken = root.join("broken");
+        fs::create_dir_all(broken.join("bin")).unwrap();
+        std::os::unix::fs::symlink("/pypilot/removed/python3", broken.join(UNIX_PYTHON_EXEC))
+            .unwrap();
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::create_dir_all(root.join(".hidden/bin")).unwrap();
+        fs::write(root.join(".hidden/pyvenv.cfg"), "").unwrap();
+        let venvs = VENVMANAGER.collect_venvs(fs::read_dir(&root).unwrap());
+        let names: Vec<&str> = venvs.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["broken"]);
+        let issues = doctor::check(&venvs[0]).await;
+        fs::remove_dir_all(&root).unwrap();
+        assert!(issues.contains(&doctor::Issue::BrokenSymlink(broken.join(UNIX_PYTHON_EXEC))));
+    }
+
     #[tokio::test]
     async fn test_check_if_exists() {
         let exists = VENVMANAGER
@@ -186,6 +664,14 @@ mod tests {
         assert_eq!(venv.unwrap().name, "test_venv");
     }
 
+    #[tokio::test]
+    async fn test_find_venv_invalid_name() {
+        let venv = VENVMANAGER
+            .find_venv(Some("../..".to_string()), None, "delete")
+            .await;
+        assert!(venv.is_none());
+    }
+
     #[tokio::test]
     async fn test_find_venv_by_name_pos() {
         let venv = VENVMANAGER
@@ -204,54 +690,91 @@ mod tests {
         assert!(venvs.is_empty());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_venvs_non_utf8() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+        let root = std::env::temp_dir().join(format!("pypilot_non_utf8_{}", std::process::id()));
+        let venv_dir = root.join(OsStr::from_bytes(b"venv\xff"));
+        fs::create_dir_all(venv_dir.join("bin")).unwrap();
+        fs::write(venv_dir.join(UNIX_PYTHON_EXEC), "").unwrap();
+        let venvs = VENVMANAGER.collect_venvs(fs::read_dir(&root).unwrap());
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(venvs.len(), 1);
+        assert_eq!(venvs[0].path, venv_dir);
+        assert_eq!(venvs[0].name, "venv\u{FFFD}");
+    }
+
     #[tokio::test]
     async fn test_print_table() {
-        let mut venvs = vec![
+        let venvs = vec![
             Venv {
                 name: "venv1".to_string(),
                 python_version: "3.10".to_string(),
-                path: "/some/path".to_string(),
+                path: "/some/path".into(),
                 packages: Vec::new(),
                 default: false,
+                tags: Vec::new(),
+                description: None,
+                index_profile: None,
+                index_url: None,
+                wheelhouse: None,
+                offline: false,
                 settings: settings::Settings::get_settings(),
             },
             Venv {
                 name: "venv2".to_string(),
                 python_version: "3.11".to_string(),
-                path: "/other/path".to_string(),
+                path: "/other/path".into(),
                 packages: Vec::new(),
                 default: true,
+                tags: Vec::new(),
+                description: None,
+                index_profile: None,
+                index_url: None,
+                wheelhouse: None,
+                offline: false,
                 settings: settings::Settings::get_settings(),
             },
         ];
-        VENVMANAGER.print_venv_table(&mut venvs).await;
+        VENVMANAGER.print_venv_table(&to_infos(venvs), &[]);
     }
 
     #[tokio::test]
     async fn test_print_venv_table() {
-        let mut venvs = vec![
+        let venvs = vec![
             Venv {
                 name: "venv1".to_string(),
                 python_version: "3.10".to_string(),
-                path: "/some/path".to_string(),
+                path: "/some/path".into(),
                 packages: Vec::new(),
                 default: false,
+                tags: Vec::new(),
+                description: None,
+                index_profile: None,
+                index_url: None,
+                wheelhouse: None,
+                offline: false,
                 settings: settings::Settings::get_settings(),
             },
             Venv {
                 name: "venv2".to_string(),
                 python_version: "3.11".to_string(),
-                path: "/other/path".to_string(),
+                path: "/other/path".into(),
                 packages: Vec::new(),
                 default: true,
+                tags: Vec::new(),
+                description: None,
+                index_profile: None,
+                index_url: None,
+                wheelhouse: None,
+                offline: false,
                 settings: settings::Settings::get_settings(),
             },
         ];
 
         let mut output = Vec::new();
-        VENVMANAGER
-            .print_venv_table_to(&mut output, &mut venvs)
-            .await;
+        VENVMANAGER.print_venv_table_to(&mut output, &to_infos(venvs), &[]);
 
         let output_str = String::from_utf8(output).unwrap();
         assert!(output_str.contains("venv1"));
@@ -260,6 +783,270 @@ mod tests {
         assert!(output_str.contains("3.11"));
     }
 
+    #[test]
+    fn test_print_venv_table_columns() {
+        let mut infos = to_infos(vec![Venv::from_path("/some/venv1".into())]);
+        infos[0].size = Some(2048);
+        let mut output = Vec::new();
+        VENVMANAGER.print_venv_table_to(&mut output, &infos, &[Column::Size, Column::Path]);
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("Size"));
+        assert!(output_str.contains("2.0 KB"));
+        assert!(output_str.contains("/some/venv1"));
+    }
+
+    #[tokio::test]
+    async fn test_collect_info() {
+        let venvs = vec![
+            Venv::from_path("/pypilot/missing/venv1".into()),
+            Venv::from_path("/pypilot/missing/venv2".into()),
+        ];
+        let infos = VENVMANAGER
+            .collect_info(venvs, &[Column::Size, Column::Packages])
+            .await;
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos[0].venv.name, "venv1");
+        assert_eq!(infos[0].size, Some(0));
+        assert_eq!(infos[1].packages, Some(0));
+    }
+
+    #[test]
+    fn test_print_usage_table() {
+        let report = UsageReport {
+            envs: vec![crate::usage::EnvUsage {
+                name: "venv1".to_string(),
+                total: 4096,
+                unique: 1024,
+                shared: 3072,
+            }],
+            apparent: 4096,
+            actual: 2048,
+        };
+        let mut output = Vec::new();
+        VENVMANAGER.print_usage_table_to(&mut output, &report);
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("venv1"));
+        assert!(output_str.contains("3.0 KB"));
+        assert!(output_str.contains("actual disk usage: 2.0 KB"));
+    }
+
+    #[test]
+    fn test_print_details() {
+        let mut venv = Venv::new("venv1".to_string(), "3.11.4".to_string(), vec![], false);
+        venv.tags = vec!["gpu".to_string()];
+        let info = to_infos(vec![venv]).remove(0);
+        let snapshot = Snapshot {
+            id: "20260101-120000".to_string(),
+            label: Some("stable".to_string()),
+            created: chrono::Utc::now(),
+            automatic: false,
+            path: info.venv.path.clone(),
+            python_version: "3.11.4".to_string(),
+            packages: vec!["six==1.16.0".to_string()],
+            archive: None,
+        };
+        let mut output = Vec::new();
+        VENVMANAGER.print_details_to(&mut output, &info, &[]);
+        VENVMANAGER.print_details_to(&mut output, &info, &[snapshot]);
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("venv1"));
+        assert!(output_str.contains("gpu"));
+        assert!(output_str.contains("No snapshots"));
+        assert!(output_str.contains("20260101-120000"));
+        assert!(output_str.contains("stable"));
+    }
+
+    #[test]
+    fn test_print_diff_table() {
+        let mut diff = PackageDiff {
+            left: "venv1".to_string(),
+            right: "venv2".to_string(),
+            entries: vec![crate::diff::DiffEntry {
+                name: "requests".to_string(),
+                left: Some("2.31.0".to_string()),
+                right: Some("2.32.0".to_string()),
+                change: Change::Changed,
+            }],
+        };
+        let mut output = Vec::new();
+        VENVMANAGER.print_diff_table_to(&mut output, &diff);
+        diff.entries.clear();
+        VENVMANAGER.print_diff_table_to(&mut output, &diff);
+
+        let output_str = String::from_utf8(output).unwrap();
+        assert!(output_str.contains("venv2"));
+        assert!(output_str.contains("2.32.0"));
+        assert!(output_str.contains("venv1 and venv2 have the same packages"));
+    }
+
+    #[test]
+    fn test_print_outdated_table() {
+        let package = Outdated {
+            name: "requests".to_string(),
+            installed: "2.31.0".parse().unwrap(),
+            latest: "3.0.0".parse().unwrap(),
+            bump: Bump::Major,
+        };
+        let mut output = Vec::new();
+        VENVMANAGER
+            .print_outdated_table_to(&mut output, &[("venv1".to_string(), vec![package.clone()])]);
+        let single = String::from_utf8(output).unwrap();
+        assert!(single.contains("3.0.0"));
+        assert!(single.contains("major"));
+        assert!(!single.contains("Environment"));
+
+        let mut output = Vec::new();
+        VENVMANAGER.print_outdated_table_to(
+            &mut output,
+            &[
+                ("venv1".to_string(), vec![package.clone()]),
+                ("venv2".to_string(), vec![package]),
+            ],
+        );
+        let multiple = String::from_utf8(output).unwrap();
+        assert!(multiple.contains("Environment"));
+        assert!(multiple.contains("venv2"));
+    }
+
+    #[test]
+    fn test_print_audit_table() {
+        let finding = Finding {
+            package: "requests".to_string(),
+            installed: "2.31.0".parse().unwrap(),
+            id: "GHSA-9wx4-h78v-vm56".to_string(),
+            aliases: vec!["CVE-2024-35195".to_string()],
+            summary: "Requests leaks proxy credentials".to_string(),
+            severity: Some("MODERATE".to_string()),
+            fixed: Some("2.32.0".parse().unwrap()),
+        };
+        let mut output = Vec::new();
+        VENVMANAGER.print_audit_table_to(&mut output, &[("venv1".to_string(), vec![finding])]);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("GHSA-9wx4-h78v-vm56"));
+        assert!(output.contains("CVE-2024-35195"));
+        assert!(output.contains("2.32.0"));
+        assert!(output.contains("MODERATE"));
+        assert!(!output.contains("Environment"));
+    }
+
+    #[test]
+    fn test_print_license_table() {
+        let packages = vec![PackageLicense {
+            name: "six".to_string(),
+            version: "1.16.0".to_string(),
+            license: "MIT".to_string(),
+            classifiers: vec![],
+            verdict: Verdict::NotAllowed,
+        }];
+        let mut output = Vec::new();
+        VENVMANAGER.print_license_table_to(&mut output, &packages, false);
+        let plain = String::from_utf8(output).unwrap();
+        assert!(plain.contains("MIT"));
+        assert!(!plain.contains("Status"));
+
+        let mut output = Vec::new();
+        VENVMANAGER.print_license_table_to(&mut output, &packages, true);
+        let checked = String::from_utf8(output).unwrap();
+        assert!(checked.contains("Status"));
+        assert!(checked.contains("not allowed"));
+    }
+
+    #[test]
+    fn test_resolve_prefix() {
+        let names = ["ml-base", "ml-gpu", "scratch"];
+        assert_eq!(VENVMANAGER.resolve_prefix("scr", &names), Ok(Some(2)));
+        assert_eq!(VENVMANAGER.resolve_prefix("ml-b", &names), Ok(Some(0)));
+        assert_eq!(VENVMANAGER.resolve_prefix("web", &names), Ok(None));
+        assert!(VENVMANAGER.resolve_prefix("ml", &names).is_err());
+    }
+
+    #[test]
+    fn test_get_indices() {
+        let cursor = std::io::Cursor::new("1, 3 3\n");
+        assert_eq!(VENVMANAGER.get_indices(cursor, 5), Ok(vec![1, 3]));
+        let cursor = std::io::Cursor::new("1 9\n");
+        assert!(VENVMANAGER.get_indices(cursor, 5).is_err());
+        let cursor = std::io::Cursor::new("c\n");
+        assert!(VENVMANAGER.get_indices(cursor, 5).is_err());
+        let cursor = std::io::Cursor::new("\n");
+        assert!(VENVMANAGER.get_indices(cursor, 5).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_find_venvs_names() {
+        let venvs = VENVMANAGER
+            .find_venvs(
+                vec![
+                    "scratch1".to_string(),
+                    "scratch2".to_string(),
+                    "scratch1".to_string(),
+                ],
+                "delete",
+            )
+            .await;
+        let names: Vec<_> = venvs.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["scratch1", "scratch2"]);
+    }
+
+    #[tokio::test]
+    async fn test_find_venvs_invalid() {
+        let venvs = VENVMANAGER
+            .find_venvs(vec!["ok".to_string(), "../..".to_string()], "delete")
+            .await;
+        assert!(venvs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_name_exact_for_delete() {
+        let venvs = vec![
+            Venv::new(
+                "pypilot-resolve-ml".to_string(),
+                "".to_string(),
+                vec![],
+                false,
+            ),
+            Venv::new(
+                "pypilot-resolve-web".to_string(),
+                "".to_string(),
+                vec![],
+                false,
+            ),
+        ];
+        let used = VENVMANAGER
+            .resolve_name("pypilot-resolve-m", &venvs, "activate")
+            .await
+            .unwrap();
+        assert_eq!(used.name, "pypilot-resolve-ml");
+        let deleted = VENVMANAGER
+            .resolve_name("pypilot-resolve-m", &venvs, "delete")
+            .await;
+        assert!(deleted.err().unwrap().contains("'pypilot-resolve-ml'"));
+        assert!(VENVMANAGER
+            .resolve_name("pypilot-resolve-w", &venvs, "rename")
+            .await
+            .is_err());
+        assert!(VENVMANAGER
+            .resolve_name("pypilot-resolve", &venvs, "activate")
+            .await
+            .is_err());
+        let missing = VENVMANAGER
+            .resolve_name("pypilot-other", &venvs, "delete")
+            .await
+            .unwrap();
+        assert_eq!(missing.name, "pypilot-other");
+    }
+
+    #[tokio::test]
+    async fn test_find_venvs_glob_no_match() {
+        let venvs = VENVMANAGER
+            .find_venvs(vec!["pypilot-no-match-*".to_string()], "delete")
+            .await;
+        assert!(venvs.is_empty());
+    }
+
     #[test]
     fn test_get_index_valid() {
         let cursor = std::io::Cursor::new("2\n");
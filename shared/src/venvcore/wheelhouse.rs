@@ -0,0 +1,340 @@
+use super::{
+    site_packages::{record_paths, InstalledDistribution},
+    venv::Venv,
+};
+use crate::constants::DIST_INFO_METADATA;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+pub const REQUIREMENTS_FILE: &str = "requirements.txt";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct WheelhouseReport {
+    pub built: Vec<PathBuf>,
+    pub requirements: Vec<String>,
+    pub skipped: Vec<(String, String)>,
+    pub outside: Vec<(String, Vec<String>)>,
+}
+
+struct Distribution {
+    dist_info: PathBuf,
+    site_packages: PathBuf,
+    name: String,
+    version: String,
+}
+
+pub fn build(venv: &Venv, dir: &Path) -> Result<WheelhouseReport, String> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Error creating wheelhouse {}: {}", dir.display(), e))?;
+    let mut report = WheelhouseReport::default();
+    let mut dists = venv.distributions();
+    dists.sort_by(|a, b| a.dist_info.cmp(&b.dist_info));
+    for installed in dists {
+        let label = installed
+            .dist_info
+            .file_stem()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let result = distribution(installed).and_then(|dist| {
+            let (wheel, outside) = build_wheel(&dist, dir)?;
+            Ok((wheel, outside, format!("{}=={}", dist.name, dist.version)))
+        });
+        match result {
+            Ok((wheel, outside, requirement)) => {
+                report.built.push(wheel);
+                report.requirements.push(requirement);
+                if !outside.is_empty() {
+                    report.outside.push((label, outside));
+                }
+            }
+            Err(e) => report.skipped.push((label, e)),
+        }
+    }
+    report.requirements.sort_by_key(|r| r.to_lowercase());
+    let mut content = report.requirements.join("\n");
+    content.push('\n');
+    fs::write(dir.join(REQUIREMENTS_FILE), content)
+        .map_err(|e| format!("Error writing {}: {}", REQUIREMENTS_FILE, e))?;
+    Ok(report)
+}
+
+fn distribution(installed: InstalledDistribution) -> Result<Distribution, String> {
+    if !installed.dist_info.join(DIST_INFO_METADATA).is_file() {
+        return Err("METADATA is missing".to_string());
+    }
+    if installed.origin.is_editable() {
+        return Err("editable install".to_string());
+    }
+    Ok(Distribution {
+        site_packages: installed.site_packages().to_path_buf(),
+        dist_info: installed.dist_info,
+        name: installed.name,
+        version: installed.version,
+    })
+}
+
+pub fn wheel_tag(wheel_file: &str) -> Option<String> {
+    let mut parts: [Vec<&str>; 3] = Default::default();
+    for tag in wheel_file.lines().filter_map(|l| l.strip_prefix("Tag:")) {
+        let fields: Vec<&str> = tag.trim().splitn(3, '-').collect();
+        if fields.len() != 3 {
+            continue;
+        }
+        for (part, field) in parts.iter_mut().zip(fields) {
+            for value in field.split('.') {
+                if !part.contains(&value) {
+                    part.push(value);
+                }
+            }
+        }
+    }
+    if parts[0].is_empty() {
+        return None;
+    }
+    Some(
+        parts
+            .iter()
+            .map(|p| p.join("."))
+            .collect::<Vec<_>>()
+            .join("-"),
+    )
+}
+
+fn outside(path: &str) -> bool {
+    path.starts_with('/') || path.split('/').any(|c| c == "..")
+}
+
+fn included(path: &str, dist_info_name: &str) -> bool {
+    let excluded_meta = ["INSTALLER", "REQUESTED", "RECORD", "direct_url.json"]
+        .iter()
+        .any(|f| path == format!("{}/{}", dist_info_name, f));
+    !excluded_meta
+        && !outside(path)
+        && !path.split('/').any(|c| c == "__pycache__")
+        && !path.ends_with(".pyc")
+}
+
+fn record_hash(data: &[u8]) -> String {
+    format!("sha256={}", URL_SAFE_NO_PAD.encode(Sha256::digest(data)))
+}
+
+fn build_wheel(dist: &Distribution, dir: &Path) -> Result<(PathBuf, Vec<String>), String> {
+    let dist_info_name = dist
+        .dist_info
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let wheel_file = fs::read_to_string(dist.dist_info.join("WHEEL"))
+        .map_err(|_| "WHEEL is missing".to_string())?;
+    let tag = wheel_tag(&wheel_file).ok_or("WHEEL has no tag")?;
+    let record = fs::read_to_string(dist.dist_info.join("RECORD"))
+        .map_err(|_| "RECORD is missing".to_string())?;
+    let paths = record_paths(&record);
+    let outside: Vec<String> = paths.iter().filter(|p| outside(p)).cloned().collect();
+    let (mut files, meta): (Vec<String>, Vec<String>) = paths
+        .into_iter()
+        .filter(|p| included(p, &dist_info_name))
+        .partition(|p| !p.starts_with(&format!("{}/", dist_info_name)));
+    files.extend(meta);
+    if let Some(missing) = files.iter().find(|p| !dist.site_packages.join(p).is_file()) {
+        return Err(format!("{} is missing", missing));
+    }
+
+    let stem = dist_info_name.trim_end_matches(".dist-info");
+    let wheel = dir.join(format!("{}-{}.whl", stem, tag));
+    let tmp = wheel.with_extension("whl.tmp");
+    let file =
+        fs::File::create(&tmp).map_err(|e| format!("Error creating {}: {}", tmp.display(), e))?;
+    let mut zip = ZipWriter::new(file);
+    let mut new_record = String::new();
+    let result = (|| -> Result<(), String> {
+        for path in &files {
+            let source = dist.site_packages.join(path);
+            let data = fs::read(&source).map_err(|e| format!("Error reading {}: {}", path, e))?;
+            zip.start_file(path.as_str(), file_options(&source))
+                .map_err(|e| e.to_string())?;
+            zip.write_all(&data).map_err(|e| e.to_string())?;
+            new_record.push_str(&format!("{},{},{}\n", path, record_hash(&data), data.len()));
+        }
+        let record_name = format!("{}/RECORD", dist_info_name);
+        new_record.push_str(&format!("{},,\n", record_name));
+        zip.start_file(record_name, SimpleFileOptions::default())
+            .map_err(|e| e.to_string())?;
+        zip.write_all(new_record.as_bytes())
+            .map_err(|e| e.to_string())?;
+        zip.finish().map_err(|e| e.to_string())?;
+        Ok(())
+    })();
+    if let Err(e) = result.and_then(|_| fs::rename(&tmp, &wheel).map_err(|e| e.to_string())) {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("Error writing {}: {}", wheel.display(), e));
+    }
+    Ok((wheel, outside))
+}
+
+fn file_options(source: &Path) -> SimpleFileOptions {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(metadata) = fs::metadata(source) {
+            return options.unix_permissions(metadata.permissions().mode() & 0o777);
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = source;
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::venvcore::testutil;
+    use std::io::Read;
+
+    fn fake_venv(name: &str) -> (Venv, PathBuf) {
+        let venv = testutil::fake_venv("wheelhouse", name);
+        let sp = testutil::site_packages(&venv);
+        (venv, sp)
+    }
+
+    fn fake_dist(sp: &Path, name: &str, version: &str, files: &[(&str, &str)]) -> PathBuf {
+        let dist_info = sp.join(format!("{}-{}.dist-info", name, version));
+        fs::create_dir_all(&dist_info).unwrap();
+        let meta = format!("{}-{}.dist-info", name, version);
+        fs::write(
+            dist_info.join(DIST_INFO_METADATA),
+            format!(
+                "Metadata-Version: 2.1\nName: {}\nVersion: {}\n",
+                name, version
+            ),
+        )
+        .unwrap();
+        fs::write(
+            dist_info.join("WHEEL"),
+            "Wheel-Version: 1.0\nTag: py2-none-any\nTag: py3-none-any\n",
+        )
+        .unwrap();
+        fs::write(dist_info.join("INSTALLER"), "uv").unwrap();
+        let mut record = format!(
+            "{0}/METADATA,,\n{0}/WHEEL,,\n{0}/INSTALLER,,\n{0}/RECORD,,\n../../../bin/{1},,\n",
+            meta, name
+        );
+        for (path, content) in files {
+            let file = sp.join(path);
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(&file, content).unwrap();
+            record.push_str(&format!("{},sha256=x,{}\n", path, content.len()));
+        }
+        fs::write(dist_info.join("RECORD"), record).unwrap();
+        dist_info
+    }
+
+    #[test]
+    fn test_wheel_tag() {
+        assert_eq!(
+            wheel_tag("Tag: py2-none-any\nTag: py3-none-any\n").as_deref(),
+            Some("py2.py3-none-any")
+        );
+        assert_eq!(
+            wheel_tag(
+                "Tag: cp311-cp311-manylinux_2_17_x86_64\nTag: cp311-cp311-manylinux2014_x86_64"
+            )
+            .as_deref(),
+            Some("cp311-cp311-manylinux_2_17_x86_64.manylinux2014_x86_64")
+        );
+        assert_eq!(wheel_tag("Wheel-Version: 1.0"), None);
+    }
+
+    #[test]
+    fn test_record_paths() {
+        let paths = record_paths("six.py,sha256=abc,10\n\"a,b.py\",sha256=def,3\n\n");
+        assert_eq!(paths, vec!["six.py", "a,b.py"]);
+        assert!(included("six.py", "six-1.0.dist-info"));
+        assert!(!included("../../../bin/six", "six-1.0.dist-info"));
+        assert!(!included(
+            "six/__pycache__/x.cpython-311.pyc",
+            "six-1.0.dist-info"
+        ));
+        assert!(!included(
+            "six-1.0.dist-info/INSTALLER",
+            "six-1.0.dist-info"
+        ));
+    }
+
+    #[test]
+    fn test_build() {
+        let (venv, sp) = fake_venv("build");
+        fake_dist(
+            &sp,
+            "six",
+            "1.16.0",
+            &[
+                ("six.py", "print('six')\n"),
+                ("six/__pycache__/six.pyc", "x"),
+            ],
+        );
+        let editable = fake_dist(&sp, "mypkg", "0.1.0", &[("mypkg.pth", "/src")]);
+        fs::write(
+            editable.join("direct_url.json"),
+            r#"{"url": "file:///src", "dir_info": {"editable": true}}"#,
+        )
+        .unwrap();
+        let out = venv.path.join("wheels");
+        let report = build(&venv, &out).unwrap();
+
+        let wheel = out.join("six-1.16.0-py2.py3-none-any.whl");
+        assert_eq!(report.built, vec![wheel.clone()]);
+        assert_eq!(report.requirements, vec!["six==1.16.0"]);
+        assert_eq!(
+            report.skipped,
+            vec![("mypkg-0.1.0".to_string(), "editable install".to_string())]
+        );
+        assert_eq!(
+            report.outside,
+            vec![(
+                "six-1.16.0".to_string(),
+                vec!["../../../bin/six".to_string()]
+            )]
+        );
+        assert_eq!(
+            fs::read_to_string(out.join(REQUIREMENTS_FILE)).unwrap(),
+            "six==1.16.0\n"
+        );
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&wheel).unwrap()).unwrap();
+        let names: Vec<String> = archive.file_names().map(String::from).collect();
+        let mut record = String::new();
+        archive
+            .by_name("six-1.16.0.dist-info/RECORD")
+            .unwrap()
+            .read_to_string(&mut record)
+            .unwrap();
+        fs::remove_dir_all(&venv.path).unwrap();
+        assert_eq!(names.len(), 4);
+        assert!(names.contains(&"six.py".to_string()));
+        assert!(!names
+            .iter()
+            .any(|n| n.contains("INSTALLER") || n.contains("..")));
+        assert!(record.contains(&format!("six.py,{},13\n", record_hash(b"print('six')\n"))));
+        assert!(record.ends_with("six-1.16.0.dist-info/RECORD,,\n"));
+    }
+
+    #[test]
+    fn test_build_missing_file() {
+        let (venv, sp) = fake_venv("missing_file");
+        fake_dist(&sp, "six", "1.16.0", &[("six.py", "")]);
+        fs::remove_file(sp.join("six.py")).unwrap();
+        let out = venv.path.join("wheels");
+        let report = build(&venv, &out).unwrap();
+        fs::remove_dir_all(&venv.path).unwrap();
+        assert!(report.built.is_empty());
+        assert_eq!(report.skipped[0].1, "six.py is missing");
+    }
+}